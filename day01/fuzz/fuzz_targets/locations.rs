@@ -0,0 +1,28 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use day01::Locations;
+use util::Input;
+
+fuzz_target!(|s: &str| {
+    let Ok(locations) = Locations::try_from(Input::from(s)) else {
+        return;
+    };
+    let _ = locations.similarity_score();
+
+    // Swapping the lists can't change how far apart they are
+    let swapped = s
+        .lines()
+        .map(|line| {
+            let values = line.split_ascii_whitespace().collect::<Vec<_>>();
+            format!("{} {}", values[1], values[0])
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let swapped = Locations::try_from(Input::from(swapped)).unwrap();
+    assert_eq!(
+        locations.total_distance().ok(),
+        swapped.total_distance().ok()
+    );
+});
@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+
+use util::{parse, Input, Part, Solution};
+
+#[derive(Debug, Default)]
+pub struct Locations {
+    lhs: Vec<usize>,
+    rhs: Vec<usize>,
+}
+
+impl Locations {
+    pub fn total_distance(&self) -> Result<usize> {
+        let mut lhs = self.lhs.clone();
+        lhs.sort();
+
+        let mut rhs = self.rhs.clone();
+        rhs.sort();
+
+        let mut distance: usize = 0;
+        for (i, j) in lhs.into_iter().zip(rhs) {
+            distance = distance
+                .checked_add(i.abs_diff(j))
+                .ok_or_else(|| anyhow!("Total distance overflowed"))?;
+        }
+
+        Ok(distance)
+    }
+
+    fn occurrences(items: &[usize]) -> HashMap<usize, usize> {
+        let mut occurrences = HashMap::new();
+
+        for item in items {
+            occurrences
+                .entry(*item)
+                .and_modify(|c| *c += 1)
+                .or_insert(1);
+        }
+
+        occurrences
+    }
+
+    pub fn similarity_score(&self) -> Result<usize> {
+        let occurrences = Self::occurrences(&self.rhs);
+
+        let mut similarity: usize = 0;
+        for item in self.lhs.iter() {
+            similarity = occurrences
+                .get(item)
+                .cloned()
+                .unwrap_or(0)
+                .checked_mul(*item)
+                .and_then(|score| similarity.checked_add(score))
+                .ok_or_else(|| anyhow!("Similarity score overflowed"))?;
+        }
+
+        Ok(similarity)
+    }
+}
+
+impl TryFrom<Input> for Locations {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Input) -> Result<Self, Self::Error> {
+        let mut locations = Locations::default();
+
+        for line in value.lines() {
+            let (lhs, rhs) = line.parse(|s| parse::separated_pair(s, " "))?;
+            locations.lhs.push(lhs);
+            locations.rhs.push(rhs);
+        }
+
+        Ok(locations)
+    }
+}
+
+impl Solution for Locations {
+    type Answer = usize;
+
+    fn parse(input: Input) -> Result<Self> {
+        Self::try_from(input)
+    }
+
+    fn solve(&self, part: Part) -> Result<usize> {
+        match part {
+            Part::One => self.total_distance(),
+            Part::Two => self.similarity_score(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use util::{arbitrary_input_test, differential, example_test, generate};
+
+    example_test!(part_1_example, Locations, Part::One, 11);
+    example_test!(part_2_example, Locations, Part::Two, 31);
+
+    #[test]
+    fn overflow() -> Result<()> {
+        let input = format!("0 {0}\n0 {0}", usize::MAX);
+        assert!(Locations::try_from(Input::from(input))?
+            .total_distance()
+            .is_err());
+
+        let input = format!("{0} {0}\n{0} {0}", usize::MAX);
+        assert!(Locations::try_from(Input::from(input))?
+            .similarity_score()
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse() -> Result<()> {
+        let locations = Locations::try_from(Input::from("3\t4\n1 \t 2"))?;
+        assert_eq!(vec![3, 1], locations.lhs);
+        assert_eq!(vec![4, 2], locations.rhs);
+
+        let err = Locations::try_from(Input::from("3   4\n5   x")).unwrap_err();
+        assert_eq!(
+            "line 2: Invalid number \"x\" at column 5: invalid digit found in string",
+            format!("{err:#}")
+        );
+
+        Ok(())
+    }
+
+    /// Pairs the smallest remaining locations in each list, one at a time.
+    fn reference_total_distance(locations: &Locations) -> usize {
+        let (mut lhs, mut rhs) = (locations.lhs.clone(), locations.rhs.clone());
+
+        let mut distance = 0;
+        while let (Some(l), Some(r)) = (lhs.iter().min().copied(), rhs.iter().min().copied()) {
+            lhs.remove(lhs.iter().position(|x| *x == l).unwrap());
+            rhs.remove(rhs.iter().position(|x| *x == r).unwrap());
+            distance += l.abs_diff(r);
+        }
+
+        distance
+    }
+
+    /// Counts each location's occurrences in the right list by scanning it.
+    fn reference_similarity_score(locations: &Locations) -> usize {
+        locations
+            .lhs
+            .iter()
+            .map(|l| l * locations.rhs.iter().filter(|r| *r == l).count())
+            .sum()
+    }
+
+    #[test]
+    fn matches_reference() -> Result<()> {
+        // Few distinct locations, so the lists share plenty of them
+        let generate = |rng: &mut generate::Rng| {
+            (0..rng.range(1, 40))
+                .map(|_| format!("{}   {}", rng.range(1, 10), rng.range(1, 10)))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let parse = |input: &Input| Locations::try_from(input.clone());
+
+        differential::compare(
+            200,
+            generate,
+            |input| parse(input)?.total_distance(),
+            |input| Ok(reference_total_distance(&parse(input)?)),
+        )?;
+        differential::compare(
+            200,
+            generate,
+            |input| parse(input)?.similarity_score(),
+            |input| Ok(reference_similarity_score(&parse(input)?)),
+        )?;
+        differential::compare(
+            20,
+            |rng| generate::locations(rng, 200),
+            |input| parse(input)?.similarity_score(),
+            |input| Ok(reference_similarity_score(&parse(input)?)),
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn parses_generated_lists(
+            pairs in prop::collection::vec((0..100_000usize, 0..100_000usize), 1..50)
+        ) {
+            let input = pairs
+                .iter()
+                .map(|(lhs, rhs)| format!("{lhs}   {rhs}"))
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            let locations = Locations::try_from(Input::from(input)).unwrap();
+            prop_assert_eq!(
+                pairs.iter().map(|(lhs, _)| *lhs).collect::<Vec<_>>(),
+                locations.lhs.clone()
+            );
+            prop_assert_eq!(
+                pairs.iter().map(|(_, rhs)| *rhs).collect::<Vec<_>>(),
+                locations.rhs.clone()
+            );
+
+            let reversed = Locations {
+                lhs: locations.rhs.clone(),
+                rhs: locations.lhs.clone(),
+            };
+            prop_assert_eq!(
+                locations.total_distance().unwrap(),
+                reversed.total_distance().unwrap()
+            );
+        }
+    }
+
+    arbitrary_input_test!(
+        arbitrary_input_does_not_panic,
+        Locations,
+        "[0-9 \n-]{0,64}|\\PC{0,64}"
+    );
+}
@@ -0,0 +1,21 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use day02::{parse_reports, Levels, SafetyPolicy};
+use util::Input;
+
+fuzz_target!(|s: &str| {
+    // Skipping malformed lines leaves nothing to fail on
+    assert!(parse_reports(&Input::from(s), true).is_ok());
+
+    let Ok(levels) = Levels::try_from(s) else {
+        return;
+    };
+
+    // The Problem Dampener only ever removes levels, so can't make a safe report unsafe
+    let policy = SafetyPolicy::default();
+    if levels.is_safe(&policy) {
+        assert!(levels.is_safe_with_problem_dampener(&policy));
+    }
+});
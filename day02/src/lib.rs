@@ -0,0 +1,401 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use clap::{Arg, ArgAction, ArgMatches};
+use log::warn;
+
+use util::{parse, Input, Part, Solution};
+
+/// Rules used to decide whether a report's readings are safe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SafetyPolicy {
+    /// Smallest allowed difference between two adjacent, non-equal readings.
+    min_step: usize,
+    /// Largest allowed difference between two adjacent readings.
+    max_step: usize,
+    /// Whether two adjacent, equal readings are allowed.
+    allow_plateaus: bool,
+    /// Whether readings must be all increasing or all decreasing.
+    require_monotonic: bool,
+    /// How many levels the Problem Dampener may remove from a report.
+    max_bad_levels: usize,
+}
+
+impl Default for SafetyPolicy {
+    fn default() -> Self {
+        Self {
+            min_step: 1,
+            max_step: 3,
+            allow_plateaus: false,
+            require_monotonic: true,
+            max_bad_levels: 1,
+        }
+    }
+}
+
+impl SafetyPolicy {
+    pub fn args() -> Vec<Arg> {
+        vec![
+            Arg::new("min-step")
+                .long("min-step")
+                .default_value("1")
+                .help("smallest allowed difference between adjacent readings"),
+            Arg::new("max-step")
+                .long("max-step")
+                .default_value("3")
+                .help("largest allowed difference between adjacent readings"),
+            Arg::new("allow-plateaus")
+                .long("allow-plateaus")
+                .action(ArgAction::SetTrue)
+                .help("allow adjacent readings to be equal"),
+            Arg::new("allow-non-monotonic")
+                .long("allow-non-monotonic")
+                .action(ArgAction::SetTrue)
+                .help("allow readings to change direction"),
+            Arg::new("max-bad-levels")
+                .long("max-bad-levels")
+                .default_value("1")
+                .help("number of levels the Problem Dampener may remove"),
+        ]
+    }
+}
+
+impl TryFrom<&ArgMatches> for SafetyPolicy {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &ArgMatches) -> Result<Self, Self::Error> {
+        let parse = |name: &str| -> Result<usize> {
+            let s = value
+                .get_one::<String>(name)
+                .ok_or_else(|| anyhow!("Missing {name}"))?;
+            usize::from_str(s).map_err(|e| anyhow!("Invalid {name} {s:?}: {e}"))
+        };
+
+        let policy = Self {
+            min_step: parse("min-step")?,
+            max_step: parse("max-step")?,
+            allow_plateaus: value.get_flag("allow-plateaus"),
+            require_monotonic: !value.get_flag("allow-non-monotonic"),
+            max_bad_levels: parse("max-bad-levels")?,
+        };
+
+        if policy.min_step > policy.max_step {
+            return Err(anyhow!(
+                "Minimum step {} exceeds maximum step {}",
+                policy.min_step,
+                policy.max_step
+            ));
+        }
+
+        Ok(policy)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Levels(Vec<usize>);
+
+impl Levels {
+    /// Returns whether the step from `x` to `y` is safe, and its direction (`None` for a plateau).
+    fn are_readings_safe_and_increasing(
+        x: usize,
+        y: usize,
+        overall_increasing: &Option<bool>,
+        policy: &SafetyPolicy,
+    ) -> (bool, Option<bool>) {
+        if x == y {
+            return (policy.allow_plateaus, None);
+        }
+
+        let increasing = y > x;
+
+        match overall_increasing {
+            Some(overall_increasing)
+                if policy.require_monotonic && increasing != *overall_increasing =>
+            {
+                return (false, Some(increasing))
+            }
+            Some(_) | None => (),
+        }
+
+        let diff = if increasing { y - x } else { x - y };
+
+        (
+            (policy.min_step..=policy.max_step).contains(&diff),
+            Some(increasing),
+        )
+    }
+
+    /// Readings are safe if every adjacent pair is, so fewer than two readings are always safe.
+    fn are_readings_safe(readings: &[usize], policy: &SafetyPolicy) -> bool {
+        let mut overall_increasing = None;
+
+        for pair in readings.windows(2) {
+            let (x, y) = (pair[0], pair[1]);
+
+            let (safe, increasing) =
+                Self::are_readings_safe_and_increasing(x, y, &overall_increasing, policy);
+
+            if !safe {
+                return false;
+            }
+
+            if increasing.is_some() {
+                overall_increasing = increasing;
+            }
+        }
+
+        true
+    }
+
+    /// Whether the readings are safe after removing at most `bad_levels` of them.
+    fn are_readings_safe_with_removals(
+        readings: &[usize],
+        policy: &SafetyPolicy,
+        bad_levels: usize,
+    ) -> bool {
+        if Self::are_readings_safe(readings, policy) {
+            return true;
+        }
+
+        if bad_levels == 0 {
+            return false;
+        }
+
+        for i in 0..readings.len() {
+            let mut readings = readings.to_vec();
+            readings.remove(i);
+
+            if Self::are_readings_safe_with_removals(&readings, policy, bad_levels - 1) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    pub fn is_safe(&self, policy: &SafetyPolicy) -> bool {
+        Self::are_readings_safe(&self.0, policy)
+    }
+
+    pub fn is_safe_with_problem_dampener(&self, policy: &SafetyPolicy) -> bool {
+        Self::are_readings_safe_with_removals(&self.0, policy, policy.max_bad_levels)
+    }
+}
+
+impl TryFrom<&str> for Levels {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let levels = Levels(parse::numbers(value)?);
+
+        if levels.0.is_empty() {
+            return Err(anyhow!("Empty report"));
+        }
+
+        Ok(levels)
+    }
+}
+
+/// Parses one report per line. Malformed lines are either an error or, if `skip_invalid` is set,
+/// logged and skipped.
+pub fn parse_reports(input: &Input, skip_invalid: bool) -> Result<Vec<Levels>> {
+    let mut reports = Vec::with_capacity(input.len());
+
+    for line in input.lines() {
+        match line.parse(Levels::try_from) {
+            Ok(levels) => reports.push(levels),
+            Err(e) if skip_invalid => warn!("Skipping {e:#}"),
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(reports)
+}
+
+/// Every report in the input, along with the policy used to check them.
+pub struct Reports {
+    pub levels: Vec<Levels>,
+    pub policy: SafetyPolicy,
+}
+
+impl Solution for Reports {
+    type Answer = usize;
+
+    fn parse(input: Input) -> Result<Self> {
+        Ok(Self {
+            levels: parse_reports(&input, false)?,
+            policy: SafetyPolicy::default(),
+        })
+    }
+
+    fn solve(&self, part: Part) -> Result<usize> {
+        Ok(match part {
+            Part::One => self
+                .levels
+                .iter()
+                .filter(|l| l.is_safe(&self.policy))
+                .count(),
+            Part::Two => self
+                .levels
+                .iter()
+                .filter(|l| l.is_safe_with_problem_dampener(&self.policy))
+                .count(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use util::{arbitrary_input_test, example_test, generate, generated_test};
+
+    example_test!(part_1_answer, Reports, Part::One, 2);
+    example_test!(part_2_answer, Reports, Part::Two, 4);
+
+    #[test]
+    fn part_1_example() -> Result<()> {
+        let input = util::init_test()?;
+        assert_eq!(6, input.len());
+
+        for (i, line) in input.lines().enumerate() {
+            let levels = Levels::try_from(line.text)?;
+            let is_safe = levels.is_safe(&SafetyPolicy::default());
+            assert_eq!(
+                i == 0 || i == 5,
+                is_safe,
+                "Incorrect Safety: {:?} --> {}",
+                levels,
+                is_safe
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn part_2_example() -> Result<()> {
+        let input = util::init_test()?;
+
+        // Add an example where we need to skip the first reading
+        let input = Input::from(format!("{}\n10 2 3 4 5", input.raw()));
+
+        assert_eq!(7, input.len());
+
+        for (i, line) in input.lines().enumerate() {
+            let levels = Levels::try_from(line.text)?;
+            let is_safe = levels.is_safe_with_problem_dampener(&SafetyPolicy::default());
+            assert_eq!(
+                !(i == 1 || i == 2),
+                is_safe,
+                "Incorrect Safety: {:?} --> {}",
+                levels,
+                is_safe
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn custom_policy() -> Result<()> {
+        let plateaus = SafetyPolicy {
+            allow_plateaus: true,
+            ..SafetyPolicy::default()
+        };
+        assert!(Levels::try_from("8 6 4 4 1")?.is_safe(&plateaus));
+        assert!(!Levels::try_from("8 6 4 4 1")?.is_safe(&SafetyPolicy::default()));
+
+        let non_monotonic = SafetyPolicy {
+            require_monotonic: false,
+            ..SafetyPolicy::default()
+        };
+        assert!(Levels::try_from("1 3 2 4 5")?.is_safe(&non_monotonic));
+
+        let wide_steps = SafetyPolicy {
+            max_step: 4,
+            ..SafetyPolicy::default()
+        };
+        assert!(Levels::try_from("9 7 6 2 1")?.is_safe(&wide_steps));
+
+        let two_bad_levels = SafetyPolicy {
+            max_bad_levels: 2,
+            ..SafetyPolicy::default()
+        };
+        let levels = Levels::try_from("1 9 2 9 3")?;
+        assert!(!levels.is_safe_with_problem_dampener(&SafetyPolicy::default()));
+        assert!(levels.is_safe_with_problem_dampener(&two_bad_levels));
+
+        Ok(())
+    }
+
+    #[test]
+    fn degenerate_reports() -> Result<()> {
+        let policy = SafetyPolicy::default();
+
+        let single = Levels::try_from("7")?;
+        assert!(single.is_safe(&policy));
+        assert!(single.is_safe_with_problem_dampener(&policy));
+
+        let pair = Levels::try_from("1 9")?;
+        assert!(!pair.is_safe(&policy));
+        assert!(pair.is_safe_with_problem_dampener(&policy));
+
+        assert!(Levels::try_from("").is_err());
+        assert!(Levels::try_from("   ").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_reports_with_invalid_lines() -> Result<()> {
+        let input = Input::from("1 2 3\n\n4 x 6\n9 8 7");
+
+        let err = parse_reports(&input, false).unwrap_err();
+        assert_eq!("line 2: Empty report", format!("{err:#}"));
+
+        let reports = parse_reports(&input, true)?;
+        assert_eq!(2, reports.len());
+
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn parses_generated_reports(
+            reports in prop::collection::vec(prop::collection::vec(0..100usize, 1..10), 1..20)
+        ) {
+            let input = reports
+                .iter()
+                .map(|r| r.iter().map(|l| l.to_string()).collect::<Vec<_>>().join(" "))
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            let parsed = parse_reports(&Input::from(input), false).unwrap();
+            prop_assert_eq!(reports, parsed.iter().map(|l| l.0.clone()).collect::<Vec<_>>());
+
+            let policy = SafetyPolicy::default();
+            for levels in parsed {
+                prop_assert!(
+                    !levels.is_safe(&policy) || levels.is_safe_with_problem_dampener(&policy)
+                );
+            }
+        }
+    }
+
+    generated_test!(
+        generated_input,
+        Reports,
+        Part::One,
+        |rng| generate::reports(rng, 200, 0.0),
+        |rng| generate::reports(rng, 200, 0.3),
+        |rng| generate::reports(rng, 200, 1.0),
+    );
+
+    arbitrary_input_test!(
+        arbitrary_input_does_not_panic,
+        Reports,
+        "[0-9 \n]{0,64}|\\PC{0,64}"
+    );
+}
@@ -0,0 +1,22 @@
+use std::process::Command;
+
+use anyhow::Result;
+use serde_json::Value;
+
+#[test]
+fn stdout_only_holds_records() -> Result<()> {
+    let output = Command::new(env!("CARGO_BIN_EXE_day02"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(["-i", "test", "-v", "--skip-invalid", "--format", "ndjson"])
+        .output()?;
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout)?;
+    let records = stdout
+        .lines()
+        .map(serde_json::from_str::<Value>)
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(2, records.len());
+
+    Ok(())
+}
@@ -0,0 +1,20 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use day03::{apply_streaming, InstructionSet, Instructions};
+
+fuzz_target!(|s: &str| {
+    let instructions = Instructions::try_from(s.to_string()).unwrap();
+
+    let results = (
+        instructions.apply_unconditionally(),
+        instructions.apply_conditionally(),
+    );
+    if let (Ok(unconditional), Ok(conditional)) = results {
+        assert_eq!(
+            (unconditional, conditional),
+            apply_streaming(s.as_bytes(), &InstructionSet::default()).unwrap()
+        );
+    }
+});
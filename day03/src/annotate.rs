@@ -0,0 +1,193 @@
+use std::ops::Range;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+
+use crate::{Instruction, Instructions, TraceStep};
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Annotation {
+    Ansi,
+    Html,
+}
+
+impl FromStr for Annotation {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ansi" => Ok(Self::Ansi),
+            "html" => Ok(Self::Html),
+            _ => Err(anyhow!("Invalid annotation type: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Highlight {
+    EnabledMultiplication,
+    DisabledMultiplication,
+    Toggle,
+    Other,
+    NearMiss,
+}
+
+impl Highlight {
+    fn ansi(&self) -> &'static str {
+        match self {
+            Self::EnabledMultiplication => "\x1b[1;32m",
+            Self::DisabledMultiplication => "\x1b[2;33m",
+            Self::Toggle => "\x1b[1;36m",
+            Self::Other => "\x1b[1;35m",
+            Self::NearMiss => "\x1b[4;31m",
+        }
+    }
+
+    fn css_class(&self) -> &'static str {
+        match self {
+            Self::EnabledMultiplication => "enabled",
+            Self::DisabledMultiplication => "disabled",
+            Self::Toggle => "toggle",
+            Self::Other => "other",
+            Self::NearMiss => "near-miss",
+        }
+    }
+}
+
+/// Renders `input` with the spans of `instructions` highlighted, where `input` is the text they
+/// were parsed from.
+pub fn annotate(
+    input: &str,
+    instructions: &Instructions,
+    annotation: Annotation,
+) -> Result<String> {
+    let highlights = highlights(instructions)?;
+
+    Ok(match annotation {
+        Annotation::Ansi => ansi(input, &highlights),
+        Annotation::Html => html(input, &highlights),
+    })
+}
+
+/// Non-overlapping highlighted spans, ordered by position.
+fn highlights(instructions: &Instructions) -> Result<Vec<(Range<usize>, Highlight)>> {
+    let mut highlights = Vec::new();
+
+    instructions.apply_with(
+        false,
+        |TraceStep {
+             instruction,
+             active,
+             ..
+         }| {
+            let highlight = match instruction.value {
+                Instruction::Multiplication(_, _) if active => Highlight::EnabledMultiplication,
+                Instruction::Multiplication(_, _) => Highlight::DisabledMultiplication,
+                Instruction::Do | Instruction::DoNot => Highlight::Toggle,
+                Instruction::Custom(_, _) => Highlight::Other,
+            };
+            highlights.push((instruction.span.clone(), highlight));
+        },
+    )?;
+
+    highlights.extend(
+        instructions
+            .near_misses
+            .iter()
+            .map(|m| (m.span.clone(), Highlight::NearMiss)),
+    );
+
+    highlights.sort_by_key(|(span, _)| span.start);
+
+    let mut end = 0;
+    highlights.retain(|(span, _)| {
+        let overlaps = span.start < end;
+        end = end.max(span.end);
+        !overlaps
+    });
+
+    Ok(highlights)
+}
+
+fn ansi(input: &str, highlights: &[(Range<usize>, Highlight)]) -> String {
+    let mut output = String::with_capacity(input.len());
+
+    let mut pos = 0;
+    for (span, highlight) in highlights {
+        output.push_str(&input[pos..span.start]);
+        output.push_str(highlight.ansi());
+        output.push_str(&input[span.clone()]);
+        output.push_str(ANSI_RESET);
+        pos = span.end;
+    }
+    output.push_str(&input[pos..]);
+
+    output
+}
+
+fn html(input: &str, highlights: &[(Range<usize>, Highlight)]) -> String {
+    fn escape(s: &str) -> String {
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    }
+
+    let mut output = String::from(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<style>
+.enabled { color: green; font-weight: bold; }
+.disabled { color: goldenrod; }
+.toggle { color: darkcyan; font-weight: bold; }
+.other { color: purple; font-weight: bold; }
+.near-miss { color: red; text-decoration: underline; }
+</style>
+</head>
+<body>
+<pre>"#,
+    );
+
+    let mut pos = 0;
+    for (span, highlight) in highlights {
+        output.push_str(&escape(&input[pos..span.start]));
+        output.push_str(&format!(
+            r#"<span class="{}">{}</span>"#,
+            highlight.css_class(),
+            escape(&input[span.clone()])
+        ));
+        pos = span.end;
+    }
+    output.push_str(&escape(&input[pos..]));
+
+    output.push_str("</pre>\n</body>\n</html>\n");
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InstructionSet;
+
+    #[test]
+    fn test_annotate() -> Result<()> {
+        let input = "mul(2,4)&mul[3,7]<don't()_mul(5,5)do()";
+        let instructions = Instructions::parse(input, InstructionSet::default());
+
+        assert_eq!(
+            "\x1b[1;32mmul(2,4)\x1b[0m&\x1b[4;31mmul[3,7]\x1b[0m<\x1b[1;36mdon't()\x1b[0m_\
+             \x1b[2;33mmul(5,5)\x1b[0m\x1b[1;36mdo()\x1b[0m",
+            annotate(input, &instructions, Annotation::Ansi)?
+        );
+
+        let html = annotate(input, &instructions, Annotation::Html)?;
+        assert!(html.contains(
+            r#"<span class="enabled">mul(2,4)</span>&amp;<span class="near-miss">mul[3,7]</span>&lt;"#
+        ));
+        assert!(html.contains(r#"_<span class="disabled">mul(5,5)</span>"#));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,398 @@
+use std::collections::VecDeque;
+use std::io::{BufRead, Read};
+use std::ops::Range;
+use std::str::FromStr;
+
+use anyhow::Result;
+use log::trace;
+
+use crate::{Instruction, InstructionSet, OpCode};
+
+/// A value along with the byte range of the input it was read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spanned<T> {
+    pub value: T,
+    pub span: Range<usize>,
+}
+
+impl<T> Spanned<T> {
+    fn new(value: T, span: Range<usize>) -> Self {
+        Self { value, span }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    Instruction(Spanned<Instruction>),
+    /// A known opcode followed by a bracket that doesn't form a valid call, e.g. `mul[3,7]` or
+    /// `mul(32,64]`, along with why it was rejected.
+    NearMiss(Spanned<String>),
+}
+
+/// Scans `input` for calls to any opcode in `instruction_set`, skipping over anything else.
+pub fn tokenize(input: &str, instruction_set: &InstructionSet) -> Vec<Token> {
+    let bytes = input.as_bytes();
+    let mut tokens = Vec::new();
+
+    let mut pos = 0;
+    while pos < bytes.len() {
+        match step(bytes, pos, 0, instruction_set, true) {
+            Step::Token(token, next) => {
+                tokens.push(token);
+                pos = next;
+            }
+            Step::Skip | Step::Incomplete => pos += 1,
+        }
+    }
+
+    tokens
+}
+
+/// Largest number of unscanned bytes a [`Scanner`] buffers while waiting for a call to complete.
+const MAX_PENDING: usize = 4096;
+
+/// Scans a reader for the same tokens as [`tokenize`], a chunk at a time, so memory use doesn't
+/// grow with the size of the input. Spans are relative to the start of the reader.
+pub struct Scanner<'a, R> {
+    reader: R,
+    instruction_set: &'a InstructionSet,
+    chunk_size: usize,
+    /// Bytes read but not yet scanned, starting at `offset` in the input.
+    buffer: Vec<u8>,
+    offset: usize,
+    tokens: VecDeque<Token>,
+    eof: bool,
+}
+
+impl<'a, R: BufRead> Scanner<'a, R> {
+    pub fn new(reader: R, instruction_set: &'a InstructionSet) -> Self {
+        Self::with_chunk_size(reader, instruction_set, 64 * 1024)
+    }
+
+    pub fn with_chunk_size(
+        reader: R,
+        instruction_set: &'a InstructionSet,
+        chunk_size: usize,
+    ) -> Self {
+        Self {
+            reader,
+            instruction_set,
+            chunk_size: chunk_size.max(1),
+            buffer: Vec::new(),
+            offset: 0,
+            tokens: VecDeque::new(),
+            eof: false,
+        }
+    }
+
+    /// Reads the next chunk and scans as much of the buffer as can be decided.
+    fn scan_chunk(&mut self) -> Result<()> {
+        let read = (&mut self.reader)
+            .take(self.chunk_size as u64)
+            .read_to_end(&mut self.buffer)?;
+        self.eof = read == 0;
+
+        let mut pos = 0;
+        while pos < self.buffer.len() {
+            // Don't wait forever on a call that never ends, e.g. `mul(` followed by endless digits
+            let is_final = self.eof || (pos == 0 && self.buffer.len() >= MAX_PENDING);
+
+            match step(
+                &self.buffer,
+                pos,
+                self.offset,
+                self.instruction_set,
+                is_final,
+            ) {
+                Step::Token(token, next) => {
+                    self.tokens.push_back(token);
+                    pos = next;
+                }
+                Step::Skip => pos += 1,
+                Step::Incomplete => break,
+            }
+        }
+
+        self.buffer.drain(..pos);
+        self.offset += pos;
+
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Iterator for Scanner<'_, R> {
+    type Item = Result<Token>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(token) = self.tokens.pop_front() {
+                return Some(Ok(token));
+            }
+
+            if self.eof {
+                return None;
+            }
+
+            if let Err(e) = self.scan_chunk() {
+                self.eof = true;
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+enum Step {
+    /// A token, and the position to continue scanning from.
+    Token(Token, usize),
+    /// Nothing recognised at this position.
+    Skip,
+    /// More input is needed to decide what's at this position.
+    Incomplete,
+}
+
+/// Scans for a call starting at `pos`. `offset` is the position of `bytes` in the overall input,
+/// and `is_final` is whether `bytes` runs to the end of the input.
+fn step(
+    bytes: &[u8],
+    pos: usize,
+    offset: usize,
+    instruction_set: &InstructionSet,
+    is_final: bool,
+) -> Step {
+    if !is_final && instruction_set.is_partial_match(&bytes[pos..]) {
+        return Step::Incomplete;
+    }
+
+    let mut near_miss = None;
+
+    for opcode in instruction_set.opcodes_matching(&bytes[pos..]) {
+        match call(
+            bytes,
+            pos + opcode.name.len(),
+            opcode,
+            instruction_set.max_digits,
+            is_final,
+        ) {
+            Ok((args, end)) => {
+                let instruction = Instruction::new(opcode.name, args);
+                let span = offset + pos..offset + end;
+                trace!("{instruction:?} at {span:?}");
+                return Step::Token(Token::Instruction(Spanned::new(instruction, span)), end);
+            }
+            Err(CallError::NearMiss(reason, end)) => {
+                near_miss.get_or_insert((reason, end));
+            }
+            Err(CallError::Incomplete) => return Step::Incomplete,
+            Err(CallError::NotACall) => (),
+        }
+    }
+
+    match near_miss {
+        Some((reason, end)) => {
+            let span = offset + pos..offset + end;
+            trace!("Near miss at {span:?}: {reason}");
+            // Restart just after the opcode's start, as a call may be nested in the near miss
+            Step::Token(Token::NearMiss(Spanned::new(reason, span)), pos + 1)
+        }
+        None => Step::Skip,
+    }
+}
+
+enum CallError {
+    /// No opening bracket, so the opcode's name is just noise.
+    NotACall,
+    /// The input ended before the call could be decided.
+    Incomplete,
+    /// Why the call was rejected, and the position just past the end of the near miss.
+    NearMiss(String, usize),
+}
+
+/// Parses the bracketed argument list for `opcode` starting at `pos`, returning the arguments and
+/// the position just past the closing bracket.
+fn call(
+    bytes: &[u8],
+    mut pos: usize,
+    opcode: &OpCode,
+    max_digits: usize,
+    is_final: bool,
+) -> Result<(Vec<usize>, usize), CallError> {
+    let at = |pos: usize| match bytes.get(pos) {
+        Some(c) => Ok(Some(*c)),
+        None if is_final => Ok(None),
+        None => Err(CallError::Incomplete),
+    };
+
+    let open = match at(pos)? {
+        Some(c) if b"([{<".contains(&c) => c,
+        _ => return Err(CallError::NotACall),
+    };
+    pos += 1;
+
+    let mut reason =
+        (open != b'(').then(|| format!("expected '(' but found '{}'", char::from(open)));
+
+    let mut args = Vec::new();
+    if !at(pos)?.is_some_and(|c| b")]}>".contains(&c)) {
+        loop {
+            let start = pos;
+            while at(pos)?.is_some_and(|c| c.is_ascii_digit()) {
+                pos += 1;
+            }
+
+            if start == pos {
+                return Err(CallError::NearMiss("expected a number".to_string(), pos));
+            }
+
+            // Only ASCII digits were consumed, so this is valid UTF-8
+            let digits = std::str::from_utf8(&bytes[start..pos]).unwrap_or_default();
+            if digits.len() > max_digits {
+                reason.get_or_insert_with(|| {
+                    format!("operand {digits} has more than {max_digits} digits")
+                });
+            }
+
+            match usize::from_str(digits) {
+                Ok(arg) => args.push(arg),
+                Err(e) => {
+                    reason.get_or_insert_with(|| format!("invalid operand {digits}: {e}"));
+                }
+            }
+
+            if at(pos)? == Some(b',') {
+                pos += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    match at(pos)? {
+        Some(c) if b")]}>".contains(&c) => {
+            pos += 1;
+            if c != b')' {
+                reason.get_or_insert_with(|| format!("expected ')' but found '{}'", char::from(c)));
+            }
+        }
+        _ => {
+            return Err(CallError::NearMiss(
+                "unterminated argument list".to_string(),
+                pos,
+            ))
+        }
+    }
+
+    if args.len() != opcode.arity {
+        reason.get_or_insert_with(|| {
+            format!(
+                "{} expects {} argument(s) but found {}",
+                opcode.name,
+                opcode.arity,
+                args.len()
+            )
+        });
+    }
+
+    match reason {
+        Some(reason) => Err(CallError::NearMiss(reason, pos)),
+        None => Ok((args, pos)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn spans(input: &str) -> Vec<(&str, bool)> {
+        tokenize(input, &InstructionSet::default())
+            .into_iter()
+            .map(|t| match t {
+                Token::Instruction(i) => (&input[i.span], true),
+                Token::NearMiss(m) => (&input[m.span], false),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            vec![
+                ("mul(2,4)", true),
+                ("mul[3,7]", false),
+                ("don't()", true),
+                ("mul(5,5)", true),
+                ("mul(32,64]", false),
+                ("mul(11,8)", true),
+                ("do()", true),
+                ("mul(8,5)", true),
+            ],
+            spans("xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))")
+        );
+    }
+
+    #[test]
+    fn test_tokenize_near_misses() {
+        assert_eq!(vec![("mul(1234,5)", false)], spans("mul(1234,5)"));
+        assert_eq!(vec![("mul(4)", false)], spans("mul(4)"));
+        assert_eq!(vec![("do(1)", false)], spans("do(1)"));
+        assert_eq!(vec![("mul(2,", false)], spans("mul(2,"));
+        assert_eq!(
+            vec![("mul(", false), ("mul(2,3)", true)],
+            spans("mul(mul(2,3)")
+        );
+        assert!(spans("mul ( 2 , 4 ) do_not_mul").is_empty());
+    }
+
+    #[test]
+    fn test_scanner() -> Result<()> {
+        let input = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))\n\
+                     mul(mul(2,3)do(1)mul(1234,5)";
+        let instruction_set = InstructionSet::default();
+        let expected = tokenize(input, &instruction_set);
+
+        for chunk_size in 1..=input.len() {
+            let tokens = Scanner::with_chunk_size(input.as_bytes(), &instruction_set, chunk_size)
+                .collect::<Result<Vec<_>>>()?;
+            assert_eq!(expected, tokens, "chunk size {chunk_size}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_scanner_bounds_pending_input() -> Result<()> {
+        let input = format!("mul({}mul(2,3)", "1".repeat(3 * MAX_PENDING));
+        let instruction_set = InstructionSet::default();
+
+        let tokens = Scanner::with_chunk_size(input.as_bytes(), &instruction_set, 1024)
+            .collect::<Result<Vec<_>>>()?;
+
+        assert!(matches!(tokens.first(), Some(Token::NearMiss(_))));
+        assert!(matches!(
+            tokens.last(),
+            Some(Token::Instruction(Spanned {
+                value: Instruction::Multiplication(2, 3),
+                ..
+            }))
+        ));
+
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn scanner_matches_tokenize(
+            input in "(mul|do|don't|[(),\\[\\]0-9]|\\PC){0,200}",
+            chunk_size in 1..64usize,
+        ) {
+            let instruction_set = InstructionSet::default();
+
+            let tokens = Scanner::with_chunk_size(input.as_bytes(), &instruction_set, chunk_size)
+                .collect::<Result<Vec<_>>>()
+                .unwrap();
+
+            prop_assert_eq!(tokenize(&input, &instruction_set), tokens);
+        }
+    }
+}
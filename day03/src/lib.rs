@@ -0,0 +1,502 @@
+use std::io::BufRead;
+
+use anyhow::{anyhow, Result};
+use log::debug;
+
+use util::{Input, Part, Solution};
+
+use crate::lexer::{Scanner, Token};
+
+pub use crate::lexer::Spanned;
+
+pub mod annotate;
+mod lexer;
+
+const MUL: &str = "mul";
+const DO: &str = "do";
+const DONT: &str = "don't";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Instruction {
+    Multiplication(usize, usize),
+    Do,
+    DoNot,
+    /// An opcode registered via [`InstructionSet::register`].
+    Custom(&'static str, Vec<usize>),
+}
+
+impl Instruction {
+    fn new(name: &'static str, args: Vec<usize>) -> Self {
+        match (name, args.as_slice()) {
+            (MUL, [x, y]) => Self::Multiplication(*x, *y),
+            (DO, []) => Self::Do,
+            (DONT, []) => Self::DoNot,
+            _ => Self::Custom(name, args),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Multiplication(_, _) => MUL,
+            Self::Do => DO,
+            Self::DoNot => DONT,
+            Self::Custom(name, _) => name,
+        }
+    }
+
+    fn args(&self) -> Vec<usize> {
+        match self {
+            Self::Multiplication(x, y) => vec![*x, *y],
+            Self::Do | Self::DoNot => vec![],
+            Self::Custom(_, args) => args.clone(),
+        }
+    }
+}
+
+/// State threaded through the instructions as they're applied.
+#[derive(Debug)]
+pub struct Machine {
+    enabled: bool,
+    unconditional: bool,
+    /// Wider than the operands, so that large totals can be accumulated before overflowing.
+    result: u128,
+}
+
+impl Machine {
+    fn new(unconditional: bool) -> Self {
+        Self {
+            enabled: true,
+            unconditional,
+            result: 0,
+        }
+    }
+
+    fn step(
+        &mut self,
+        instruction_set: &InstructionSet,
+        instruction: &Spanned<Instruction>,
+    ) -> Result<()> {
+        let Spanned { value, span } = instruction;
+        let opcode = instruction_set
+            .get(value.name())
+            .ok_or_else(|| anyhow!("Unknown opcode {} at {span:?}", value.name()))?;
+
+        (opcode.operation)(self, &value.args())
+            .map_err(|e| anyhow!("Failed to apply {value:?} at {span:?}: {e}"))
+    }
+
+    /// Whether instructions with an effect on the result should currently take effect.
+    pub fn is_active(&self) -> bool {
+        self.unconditional || self.enabled
+    }
+
+    /// Adds `value` to the result, failing rather than wrapping on overflow.
+    pub fn accumulate(&mut self, value: u128) -> Result<()> {
+        self.result = self
+            .result
+            .checked_add(value)
+            .ok_or_else(|| anyhow!("Overflow adding {value} to {}", self.result))?;
+        Ok(())
+    }
+}
+
+/// The effect of applying a single instruction, for auditing how a result was reached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceStep<'a> {
+    pub instruction: &'a Spanned<Instruction>,
+    /// Whether the machine was active when the instruction was applied.
+    pub active: bool,
+    /// The result after the instruction was applied.
+    pub result: u128,
+}
+
+/// Applies an instruction's arguments to the machine.
+pub type Operation = fn(&mut Machine, &[usize]) -> Result<()>;
+
+#[derive(Debug, Clone, Copy)]
+struct OpCode {
+    name: &'static str,
+    arity: usize,
+    operation: Operation,
+}
+
+/// The opcodes that are recognised in corrupted memory, and what they do.
+#[derive(Debug, Clone)]
+pub struct InstructionSet {
+    opcodes: Vec<OpCode>,
+    /// Largest number of digits allowed in a single operand.
+    max_digits: usize,
+}
+
+impl Default for InstructionSet {
+    fn default() -> Self {
+        Self {
+            opcodes: Vec::new(),
+            max_digits: 3,
+        }
+        .register(MUL, 2, |m, args| {
+            if m.is_active() {
+                let (x, y) = (args[0] as u128, args[1] as u128);
+                m.accumulate(
+                    x.checked_mul(y)
+                        .ok_or_else(|| anyhow!("Overflow multiplying {x} by {y}"))?,
+                )?;
+            }
+            Ok(())
+        })
+        .register(DO, 0, |m, _| {
+            m.enabled = true;
+            Ok(())
+        })
+        .register(DONT, 0, |m, _| {
+            m.enabled = false;
+            Ok(())
+        })
+    }
+}
+
+impl InstructionSet {
+    /// Adds an opcode, replacing any existing opcode with the same name.
+    pub fn register(mut self, name: &'static str, arity: usize, operation: Operation) -> Self {
+        self.opcodes.retain(|o| o.name != name);
+        self.opcodes.push(OpCode {
+            name,
+            arity,
+            operation,
+        });
+
+        // Keep longer names first, so e.g. "don't" is preferred over "do"
+        self.opcodes
+            .sort_by_key(|o| std::cmp::Reverse(o.name.len()));
+        self
+    }
+
+    pub fn with_max_digits(mut self, max_digits: usize) -> Self {
+        self.max_digits = max_digits;
+        self
+    }
+
+    fn get(&self, name: &str) -> Option<&OpCode> {
+        self.opcodes.iter().find(|o| o.name == name)
+    }
+
+    /// Whether `bytes` could be the start of an opcode's name, but is too short to know.
+    fn is_partial_match(&self, bytes: &[u8]) -> bool {
+        self.opcodes
+            .iter()
+            .any(|o| o.name.len() > bytes.len() && o.name.as_bytes().starts_with(bytes))
+    }
+
+    /// Opcodes whose name is a prefix of `bytes`, longest first.
+    fn opcodes_matching<'a>(&'a self, bytes: &'a [u8]) -> impl Iterator<Item = &'a OpCode> {
+        self.opcodes
+            .iter()
+            .filter(move |o| bytes.starts_with(o.name.as_bytes()))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Instructions {
+    instruction_set: InstructionSet,
+    instructions: Vec<Spanned<Instruction>>,
+    near_misses: Vec<Spanned<String>>,
+}
+
+impl Instructions {
+    pub fn parse(input: &str, instruction_set: InstructionSet) -> Self {
+        let mut instructions = Vec::new();
+        let mut near_misses = Vec::new();
+
+        for token in lexer::tokenize(input, &instruction_set) {
+            match token {
+                Token::Instruction(i) => instructions.push(i),
+                Token::NearMiss(m) => near_misses.push(m),
+            }
+        }
+
+        Self {
+            instruction_set,
+            instructions,
+            near_misses,
+        }
+    }
+
+    fn apply(&self, unconditional: bool) -> Result<u128> {
+        self.apply_with(unconditional, |_| ())
+    }
+
+    /// Applies the instructions, calling `inspect` with the effect of each one.
+    fn apply_with<'a>(
+        &'a self,
+        unconditional: bool,
+        mut inspect: impl FnMut(TraceStep<'a>),
+    ) -> Result<u128> {
+        let mut machine = Machine::new(unconditional);
+
+        for instruction in self.instructions.iter() {
+            let active = machine.is_active();
+            machine.step(&self.instruction_set, instruction)?;
+
+            inspect(TraceStep {
+                instruction,
+                active,
+                result: machine.result,
+            });
+        }
+
+        Ok(machine.result)
+    }
+
+    pub fn trace(&self, unconditional: bool) -> Result<Vec<TraceStep<'_>>> {
+        let mut trace = Vec::with_capacity(self.instructions.len());
+        self.apply_with(unconditional, |step| trace.push(step))?;
+        Ok(trace)
+    }
+
+    pub fn apply_unconditionally(&self) -> Result<u128> {
+        self.apply(true)
+    }
+
+    pub fn apply_conditionally(&self) -> Result<u128> {
+        self.apply(false)
+    }
+}
+
+/// Applies instructions as they're scanned from `reader`, without holding them all in memory.
+/// Returns the unconditional and conditional results.
+pub fn apply_streaming(
+    reader: impl BufRead,
+    instruction_set: &InstructionSet,
+) -> Result<(u128, u128)> {
+    let mut unconditional = Machine::new(true);
+    let mut conditional = Machine::new(false);
+
+    for token in Scanner::new(reader, instruction_set) {
+        match token? {
+            Token::Instruction(instruction) => {
+                unconditional.step(instruction_set, &instruction)?;
+                conditional.step(instruction_set, &instruction)?;
+            }
+            Token::NearMiss(Spanned { value, span }) => debug!("Near miss at {span:?}: {value}"),
+        }
+    }
+
+    Ok((unconditional.result, conditional.result))
+}
+
+impl TryFrom<String> for Instructions {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Ok(Self::parse(&value, InstructionSet::default()))
+    }
+}
+
+impl Solution for Instructions {
+    type Answer = u128;
+
+    fn parse(input: Input) -> Result<Self> {
+        Ok(Self::parse(input.raw(), InstructionSet::default()))
+    }
+
+    fn solve(&self, part: Part) -> Result<u128> {
+        match part {
+            Part::One => self.apply_unconditionally(),
+            Part::Two => self.apply_conditionally(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::annotate::Annotation;
+
+    use util::{arbitrary_input_test, example_test, generate, generated_test};
+
+    example_test!(part_1_example, Instructions, Part::One, 161);
+    example_test!(part_2_example, Instructions, Part::Two, 48);
+
+    #[test]
+    fn part_1_example_streaming() -> Result<()> {
+        util::init_test_logger()?;
+
+        let (unconditional, _) =
+            apply_streaming(util::test_input_reader()?, &InstructionSet::default())?;
+
+        assert_eq!(161, unconditional);
+
+        Ok(())
+    }
+
+    #[test]
+    fn part_2_example_near_misses() -> Result<()> {
+        let input = util::example_input(Part::Two, None)?;
+
+        let instructions = <Instructions as Solution>::parse(input)?;
+
+        assert_eq!(2, instructions.near_misses.len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn custom_opcodes() -> Result<()> {
+        util::init_test_logger()?;
+
+        let instruction_set = InstructionSet::default()
+            .register("add", 2, |m, args| {
+                if m.is_active() {
+                    m.accumulate((args[0] + args[1]) as u128)?;
+                }
+                Ok(())
+            })
+            .register("sub", 2, |m, args| {
+                if m.is_active() {
+                    m.result = m
+                        .result
+                        .checked_sub((args[0] + args[1]) as u128)
+                        .ok_or_else(|| anyhow!("Result would be negative"))?;
+                }
+                Ok(())
+            });
+
+        let instructions = Instructions::parse(
+            "mul(2,3)xadd(4,5)don't()add(1,1)do()sub(1,2)add(7)",
+            instruction_set.clone(),
+        );
+        assert_eq!(
+            Instruction::Custom("add", vec![4, 5]),
+            instructions.instructions[1].value
+        );
+        assert_eq!(1, instructions.near_misses.len());
+        assert_eq!(12, instructions.apply_conditionally()?);
+        assert_eq!(14, instructions.apply_unconditionally()?);
+
+        let instructions = Instructions::parse("sub(1,2)", instruction_set);
+        assert!(instructions.apply_conditionally().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn operand_limits_and_overflow() -> Result<()> {
+        util::init_test_logger()?;
+
+        let input = "mul(1234,5)mul(18446744073709551615,18446744073709551615)";
+
+        let instructions = Instructions::parse(input, InstructionSet::default());
+        assert_eq!(0, instructions.apply_unconditionally()?);
+        assert_eq!(2, instructions.near_misses.len());
+
+        let instructions = Instructions::parse(input, InstructionSet::default().with_max_digits(4));
+        assert_eq!(6170, instructions.apply_unconditionally()?);
+
+        let instructions =
+            Instructions::parse(input, InstructionSet::default().with_max_digits(20));
+        assert_eq!(
+            6170 + (u64::MAX as u128) * (u64::MAX as u128),
+            instructions.apply_unconditionally()?
+        );
+
+        let input = format!("{input}mul(18446744073709551615,18446744073709551615)");
+        let instructions =
+            Instructions::parse(&input, InstructionSet::default().with_max_digits(20));
+        let err = instructions.apply_unconditionally().unwrap_err();
+        assert!(err.to_string().contains("Overflow"), "{err}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn trace() -> Result<()> {
+        let instructions =
+            Instructions::try_from("mul(2,4)don't()mul(5,5)do()mul(8,5)".to_string())?;
+
+        let trace = instructions
+            .trace(false)?
+            .into_iter()
+            .map(|s| (s.instruction.value.clone(), s.active, s.result))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                (Instruction::Multiplication(2, 4), true, 8),
+                (Instruction::DoNot, true, 8),
+                (Instruction::Multiplication(5, 5), false, 8),
+                (Instruction::Do, false, 8),
+                (Instruction::Multiplication(8, 5), true, 48),
+            ],
+            trace
+        );
+
+        Ok(())
+    }
+
+    fn corrupted_memory() -> impl Strategy<Value = String> {
+        prop::collection::vec(
+            prop_oneof![
+                Just("mul".to_string()),
+                Just("do()".to_string()),
+                Just("don't()".to_string()),
+                "[(),\\[\\]{}<>]",
+                "[0-9]{1,4}",
+                "\\PC{0,3}",
+            ],
+            0..40,
+        )
+        .prop_map(|fragments| fragments.concat())
+    }
+
+    proptest! {
+        #[test]
+        fn applies_generated_multiplications(
+            calls in prop::collection::vec((0..1000usize, 0..1000usize, "[a-z&!^ ]{0,5}"), 0..20)
+        ) {
+            let input = calls
+                .iter()
+                .map(|(x, y, noise)| format!("{noise}mul({x},{y})"))
+                .collect::<String>();
+
+            let expected = calls.iter().map(|(x, y, _)| (x * y) as u128).sum::<u128>();
+            let instructions = Instructions::parse(&input, InstructionSet::default());
+            prop_assert_eq!(expected, instructions.apply_unconditionally().unwrap());
+            prop_assert_eq!(expected, instructions.apply_conditionally().unwrap());
+        }
+
+        #[test]
+        fn streaming_matches_parsing(input in corrupted_memory()) {
+            let instruction_set = InstructionSet::default();
+            let instructions = Instructions::parse(&input, instruction_set.clone());
+
+            let results = (
+                instructions.apply_unconditionally().unwrap(),
+                instructions.apply_conditionally().unwrap(),
+            );
+            prop_assert_eq!(
+                results,
+                apply_streaming(input.as_bytes(), &instruction_set).unwrap()
+            );
+
+            annotate::annotate(&input, &instructions, Annotation::Ansi).unwrap();
+            annotate::annotate(&input, &instructions, Annotation::Html).unwrap();
+        }
+    }
+
+    generated_test!(generated_input_part_1, Instructions, Part::One, |rng| {
+        let (input, unconditional, _) = generate::corrupted_memory(rng, 1000);
+        (input, unconditional)
+    });
+    generated_test!(generated_input_part_2, Instructions, Part::Two, |rng| {
+        let (input, _, conditional) = generate::corrupted_memory(rng, 1000);
+        (input, conditional)
+    });
+
+    arbitrary_input_test!(
+        arbitrary_input_does_not_panic,
+        Instructions,
+        corrupted_memory()
+    );
+}
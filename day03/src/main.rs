@@ -1,122 +1,108 @@
+use std::fs;
+use std::io::Read;
 use std::str::FromStr;
 
-use anyhow::{anyhow, Result};
-use regex::Regex;
-
-#[derive(Debug)]
-enum Instruction {
-    Multiplication(usize, usize),
-    Do,
-    DoNot,
+use anyhow::Result;
+use clap::{Arg, ArgAction, ArgMatches};
+
+use day03::annotate::{self, Annotation};
+use day03::{apply_streaming, InstructionSet, Instructions};
+use util::{generate, Cli, Parsed, Part, Reporter};
+
+fn args() -> Vec<Arg> {
+    vec![
+        Arg::new("annotate").long("annotate").help(format!(
+            "print the input with recognised instructions highlighted, {:?} or {:?}",
+            Annotation::Ansi,
+            Annotation::Html
+        )),
+        Arg::new("output")
+            .long("output")
+            .requires("annotate")
+            .help("write the annotated input to this file instead of stdout"),
+        Arg::new("max-digits")
+            .long("max-digits")
+            .default_value("3")
+            .help("largest number of digits allowed in an operand"),
+        Arg::new("trace")
+            .long("trace")
+            .action(ArgAction::SetTrue)
+            .help("print the effect of each instruction when applied conditionally"),
+    ]
 }
 
-#[derive(Debug, Default)]
-struct Instructions(Vec<Instruction>);
-
-impl Instructions {
-    fn apply(&self, unconditional: bool) -> usize {
-        let mut enabled = true;
-
-        let mut result = 0;
-        for instruction in self.0.iter() {
-            match instruction {
-                Instruction::Do => enabled = true,
-                Instruction::DoNot => enabled = false,
-                Instruction::Multiplication(x, y) => {
-                    if unconditional || enabled {
-                        result += *x * *y
-                    }
-                }
-            }
-        }
-
-        result
-    }
-
-    fn apply_unconditionally(&self) -> usize {
-        self.apply(true)
+fn annotate(
+    matches: &ArgMatches,
+    reporter: &Reporter,
+    input: &str,
+    instructions: &Instructions,
+) -> Result<()> {
+    let Some(annotation) = matches.get_one::<String>("annotate") else {
+        return Ok(());
+    };
+
+    let annotated = annotate::annotate(input, instructions, Annotation::from_str(annotation)?)?;
+
+    match matches.get_one::<String>("output") {
+        Some(path) => fs::write(path, annotated)?,
+        None => reporter.diagnostic(annotated),
     }
 
-    fn apply_conditionally(&self) -> usize {
-        self.apply(false)
-    }
+    Ok(())
 }
 
-impl TryFrom<String> for Instructions {
-    type Error = anyhow::Error;
-
-    fn try_from(value: String) -> Result<Self, Self::Error> {
-        let instruction_regex =
-            Regex::new(r"(mul\((?<x>\d{1,3}),(?<y>\d{1,3})\))|(?<do>do\(\))|(?<dont>don't\(\))")?;
-
-        let mut instructions = Instructions::default();
-
-        for m in instruction_regex.captures_iter(&value) {
-            if m.name("do").is_some() {
-                instructions.0.push(Instruction::Do);
-            } else if m.name("dont").is_some() {
-                instructions.0.push(Instruction::DoNot);
-            } else {
-                let x = usize::from_str(
-                    m.name("x")
-                        .ok_or_else(|| anyhow!("Missing x in multiplication"))?
-                        .as_str(),
-                )?;
-
-                let y = usize::from_str(
-                    m.name("y")
-                        .ok_or_else(|| anyhow!("Missing y in multiplication"))?
-                        .as_str(),
-                )?;
-
-                instructions.0.push(Instruction::Multiplication(x, y));
+fn main() -> Result<()> {
+    let cli = Cli::new(3).args(args()).generator([], |rng, size, _| {
+        Ok(generate::corrupted_memory(rng, size).0)
+    });
+    let (config, mut reader) = match cli.init_reader()? {
+        Parsed::Solve(config, reader) => (config, reader),
+        Parsed::Generated(input) => {
+            println!("{input}");
+            return Ok(());
+        }
+    };
+    let matches = &config.matches;
+    let mut reporter = config.reporter();
+
+    let max_digits = matches
+        .get_one::<String>("max-digits")
+        .map(|s| usize::from_str(s))
+        .unwrap()?;
+    let instruction_set = InstructionSet::default().with_max_digits(max_digits);
+
+    if matches.contains_id("annotate") || matches.get_flag("trace") {
+        // Annotating and tracing need the whole input, so there's no benefit to streaming
+        let mut input = String::new();
+        reader.read_to_string(&mut input)?;
+
+        let instructions = reporter.parse(|| Ok(Instructions::parse(&input, instruction_set)))?;
+        annotate(matches, &reporter, &input, &instructions)?;
+
+        if matches.get_flag("trace") {
+            for step in instructions.trace(false)? {
+                reporter.diagnostic(format!(
+                    "{:?} {:?} active={} result={}",
+                    step.instruction.span, step.instruction.value, step.active, step.result
+                ));
             }
         }
 
-        Ok(instructions)
+        reporter.solve(Part::One, "Unconditional Result", || {
+            instructions.apply_unconditionally()
+        })?;
+        reporter.solve(Part::Two, "Conditional Result", || {
+            instructions.apply_conditionally()
+        })?;
+    } else {
+        // Streaming solves both parts in the same pass as parsing, so it's all timed as parsing
+        let (unconditional, conditional) =
+            reporter.parse(|| apply_streaming(reader, &instruction_set))?;
+
+        reporter.solve(Part::One, "Unconditional Result", || Ok(unconditional))?;
+        reporter.solve(Part::Two, "Conditional Result", || Ok(conditional))?;
     }
-}
-
-fn main() -> Result<()> {
-    let input = util::init()?.join("\n");
-
-    let instructions = Instructions::try_from(input)?;
-
-    let result = instructions.apply_unconditionally();
-    println!("Unconditional Result: {result}");
-
-    let result = instructions.apply_conditionally();
-    println!("Conditional Result: {result}");
 
+    reporter.finish();
     Ok(())
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn part_1_example() -> Result<()> {
-        let input = util::init_test()?.join("\n");
-
-        let instructions = Instructions::try_from(input)?;
-
-        assert_eq!(161, instructions.apply_unconditionally());
-
-        Ok(())
-    }
-
-    #[test]
-    fn part_2_example() -> Result<()> {
-        util::init_test_logger()?;
-
-        let input =
-            "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))".to_string();
-
-        let instructions = Instructions::try_from(input)?;
-
-        assert_eq!(48, instructions.apply_conditionally());
-
-        Ok(())
-    }
-}
@@ -0,0 +1,19 @@
+use anyhow::Result;
+
+use day03::{InstructionSet, Instructions};
+
+#[test]
+fn register_add() -> Result<()> {
+    let instruction_set = InstructionSet::default().register("add", 2, |m, args| {
+        if m.is_active() {
+            m.accumulate(args[0] as u128 + args[1] as u128)?;
+        }
+        Ok(())
+    });
+
+    let instructions = Instructions::parse("mul(2,3)add(4,5)don't()add(1,1)", instruction_set);
+    assert_eq!(17, instructions.apply_unconditionally()?);
+    assert_eq!(15, instructions.apply_conditionally()?);
+
+    Ok(())
+}
@@ -1,9 +1,11 @@
 use std::iter::Iterator;
+use std::str::FromStr;
 
 use anyhow::Result;
+use clap::Arg;
 use log::{debug, trace};
 
-use util::Direction;
+use util::{generate, grid, parse, Cli, Direction, Input, Parsed, Part, Solution};
 
 #[derive(Debug, Default)]
 struct Grid(Vec<Vec<char>>);
@@ -12,54 +14,29 @@ impl Grid {
     fn xmas_occurrences_from(&self, x: usize, y: usize) -> usize {
         let word: Vec<char> = "XMAS".chars().collect();
 
-        let mut occurrences = 0;
-
         if self.0[y][x] != word[0] {
-            return occurrences;
+            return 0;
         }
 
-        for neighbor in util::neighbors(&self.0, x, y, true) {
-            trace!(
-                "Matched {} in {word:?} at ({x}, {y}), checking {neighbor:?}",
-                word[0]
-            );
-
-            let mut neighbor = neighbor;
-            let mut i = 1;
-            while i < word.len() {
-                let expected = word[i];
-
-                let (n_x, n_y) = neighbor.into();
-
-                let actual = self.0[n_y][n_x];
-
-                // Going in the direction of neighbor didn't find the word
-                if actual != expected {
-                    trace!("Failed to match {expected} in {word:?} at ({n_x}, {n_y}): {actual}");
-                    break;
-                }
-
-                i += 1;
-
-                // Figure out the next neighbor to check
-                if let Some(n) = neighbor.next(&self.0) {
-                    neighbor = n;
+        Direction::ALL
+            .into_iter()
+            .filter(|&direction| {
+                // Reading along the direction until the word's length runs out or the grid ends
+                let read: Vec<char> = grid::ray(&self.0, (x as i64, y as i64), direction)
+                    .cells()
+                    .map(|(_, &c)| c)
+                    .take(word.len())
+                    .collect();
+
+                let found = read == word;
+                if found {
+                    debug!("Found {word:?} from ({x}, {y}) going {direction:?}");
                 } else {
-                    break;
+                    trace!("Read {read:?} from ({x}, {y}) going {direction:?}");
                 }
-
-                trace!("Matched {expected} in {word:?} at ({n_x}, {n_y}), checking {neighbor:?}");
-            }
-
-            // If we've successfully gone along a direction to the point we reached the end of the
-            // word, we're done
-            if i == word.len() {
-                debug!("Found {word:?} from ({x}, {y}) via {neighbor:?}");
-                occurrences += 1;
-            }
-        }
-
-        occurrences
+                found
+            })
+            .count()
     }
 
     /// Count instances of XMAS (vertical, horizontal, diagonal, backwards) in a 2D grid. One
@@ -139,46 +116,80 @@ impl Grid {
     }
 }
 
-impl From<Vec<String>> for Grid {
-    fn from(value: Vec<String>) -> Self {
-        let mut grid = Grid::default();
-        for line in value {
-            let line = line.chars().collect::<Vec<_>>();
-            grid.0.push(line);
-        }
-        grid
+impl TryFrom<Input> for Grid {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Input) -> Result<Self, Self::Error> {
+        Ok(Self(parse::grid(value.lines(), Ok)?))
+    }
+}
+
+impl Solution for Grid {
+    type Answer = usize;
+
+    fn parse(input: Input) -> Result<Self> {
+        Self::try_from(input)
+    }
+
+    fn solve(&self, part: Part) -> Result<usize> {
+        Ok(match part {
+            Part::One => self.count_xmas_occurrences(),
+            Part::Two => self.count_x_mas_occurrences(),
+        })
     }
 }
 
 fn main() -> Result<()> {
-    let input = util::init()?;
-    let grid = Grid::from(input);
+    let cli = Cli::new(4).generator(
+        [Arg::new("planted")
+            .long("planted")
+            .help("number of words to plant, defaulting to the size")],
+        |rng, size, matches| {
+            let planted = matches
+                .get_one::<String>("planted")
+                .map(|s| usize::from_str(s))
+                .transpose()?
+                .unwrap_or(size);
+            Ok(generate::word_search(rng, size, size, "XMAS", planted).0)
+        },
+    );
+    let (config, input) = match cli.init()? {
+        Parsed::Solve(config, input) => (config, input),
+        Parsed::Generated(input) => {
+            println!("{input}");
+            return Ok(());
+        }
+    };
+    let mut reporter = config.reporter();
 
-    let occurrences = grid.count_xmas_occurrences();
-    println!("XMAS Occurrences: {occurrences}");
+    let grid = reporter.parse(|| Grid::try_from(input))?;
 
-    let occurrences = grid.count_x_mas_occurrences();
-    println!("X-MAS Occurrences: {occurrences}");
+    reporter.solve(Part::One, "XMAS Occurrences", || {
+        Ok(grid.count_xmas_occurrences())
+    })?;
+    reporter.solve(Part::Two, "X-MAS Occurrences", || {
+        Ok(grid.count_x_mas_occurrences())
+    })?;
 
+    reporter.finish();
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
-    #[test]
-    fn part_1_example_simplified() -> Result<()> {
-        util::init_test_logger()?;
+    use util::{arbitrary_input_test, differential, example_test, generated_test};
 
-        let input = r"..X...
-.SAMX.
-.A..A.
-XMAS.S
-.X....";
+    example_test!(part_1_example_simplified, Grid, Part::One, "simplified", 4);
+    example_test!(part_1_example, Grid, Part::One, 18);
+    example_test!(part_2_example, Grid, Part::Two, 9);
 
-        let input = input.split("\n").map(|s| s.to_string()).collect::<Vec<_>>();
-        let grid = Grid::from(input);
+    #[test]
+    fn xmas_occurrences_simplified() -> Result<()> {
+        let grid = Grid::try_from(util::example_input(Part::One, Some("simplified"))?)?;
 
         assert_eq!(1, grid.xmas_occurrences_from(2, 0));
         assert_eq!(1, grid.xmas_occurrences_from(4, 1));
@@ -189,9 +200,8 @@ XMAS.S
     }
 
     #[test]
-    fn part_1_example() -> Result<()> {
-        let input = util::init_test()?;
-        let grid = Grid::from(input);
+    fn xmas_occurrences() -> Result<()> {
+        let grid = Grid::try_from(util::init_test()?)?;
 
         assert_eq!(1, grid.xmas_occurrences_from(4, 0));
         assert_eq!(1, grid.xmas_occurrences_from(5, 0));
@@ -206,20 +216,139 @@ XMAS.S
         assert_eq!(3, grid.xmas_occurrences_from(5, 9));
         assert_eq!(2, grid.xmas_occurrences_from(9, 9));
 
-        assert_eq!(18, grid.count_xmas_occurrences());
-
         Ok(())
     }
 
     #[test]
-    fn part_2_example() -> Result<()> {
-        let input = util::init_test()?;
-        let grid = Grid::from(input);
+    fn x_mas_occurrences() -> Result<()> {
+        let grid = Grid::try_from(util::init_test()?)?;
 
         assert!(grid.x_mas_occurrences_from(2, 1));
 
-        assert_eq!(9, grid.count_x_mas_occurrences());
-
         Ok(())
     }
+
+    #[test]
+    fn ragged_grid() {
+        let err = Grid::try_from(Input::from("XMAS\nXM")).unwrap_err();
+        assert_eq!("line 2: Expected 4 cells but found 2", format!("{err:#}"));
+    }
+
+    fn grids() -> impl Strategy<Value = Vec<Vec<char>>> {
+        (1..12usize, 1..12usize).prop_flat_map(|(width, height)| {
+            prop::collection::vec(
+                prop::collection::vec(prop::sample::select(vec!['X', 'M', 'A', 'S']), width),
+                height,
+            )
+        })
+    }
+
+    /// Checks every cell in every direction, without any early exits.
+    fn reference_xmas(grid: &[Vec<char>]) -> usize {
+        let at = |x: isize, y: isize| {
+            let (x, y) = (usize::try_from(x).ok()?, usize::try_from(y).ok()?);
+            grid.get(y)?.get(x).copied()
+        };
+
+        let mut count = 0;
+        for y in 0..grid.len() as isize {
+            for x in 0..grid[0].len() as isize {
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        let word = (0..4)
+                            .map(|i| at(x + dx * i, y + dy * i))
+                            .collect::<Option<String>>();
+                        if (dx, dy) != (0, 0) && word.as_deref() == Some("XMAS") {
+                            count += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        count
+    }
+
+    /// Checks both diagonals of every 3x3 window.
+    fn reference_x_mas(grid: &[Vec<char>]) -> usize {
+        let is_mas = |s: String| s == "MAS" || s == "SAM";
+
+        let mut count = 0;
+        for y in 0..grid.len().saturating_sub(2) {
+            for x in 0..grid[0].len().saturating_sub(2) {
+                let down = (0..3).map(|i| grid[y + i][x + i]).collect::<String>();
+                let up = (0..3).map(|i| grid[y + 2 - i][x + i]).collect::<String>();
+                if is_mas(down) && is_mas(up) {
+                    count += 1;
+                }
+            }
+        }
+
+        count
+    }
+
+    #[test]
+    fn matches_reference() -> Result<()> {
+        let generate = |rng: &mut generate::Rng| {
+            let (width, height) = (rng.range(1, 12), rng.range(1, 12));
+            (0..height)
+                .map(|_| {
+                    (0..width)
+                        .map(|_| *rng.choose(&['X', 'M', 'A', 'S', '.']))
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let parse = |input: &Input| Grid::try_from(input.clone());
+
+        differential::compare(
+            300,
+            generate,
+            |input| Ok(parse(input)?.count_xmas_occurrences()),
+            |input| Ok(reference_xmas(&parse(input)?.0)),
+        )?;
+        differential::compare(
+            300,
+            generate,
+            |input| Ok(parse(input)?.count_x_mas_occurrences()),
+            |input| Ok(reference_x_mas(&parse(input)?.0)),
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn counts_are_symmetric(rows in grids()) {
+            let input = rows
+                .iter()
+                .map(|r| r.iter().collect::<String>())
+                .collect::<Vec<_>>()
+                .join("\n");
+            let grid = Grid::try_from(Input::from(input)).unwrap();
+            prop_assert_eq!(rows.clone(), grid.0.clone());
+
+            // Searching in every direction means the counts can't depend on orientation
+            let transposed = Grid(
+                (0..rows[0].len())
+                    .map(|x| rows.iter().map(|r| r[x]).collect())
+                    .collect(),
+            );
+            let mirrored = Grid(rows.iter().map(|r| r.iter().rev().copied().collect()).collect());
+
+            for other in [transposed, mirrored] {
+                prop_assert_eq!(grid.count_xmas_occurrences(), other.count_xmas_occurrences());
+                prop_assert_eq!(grid.count_x_mas_occurrences(), other.count_x_mas_occurrences());
+            }
+        }
+    }
+
+    generated_test!(generated_input, Grid, Part::One, |rng| {
+        generate::word_search(rng, 40, 40, "XMAS", 60)
+    });
+
+    arbitrary_input_test!(
+        arbitrary_input_does_not_panic,
+        Grid,
+        "[XMAS.\n]{0,64}|\\PC{0,64}"
+    );
 }
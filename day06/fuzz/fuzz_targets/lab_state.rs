@@ -0,0 +1,17 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use day06::LabState;
+use util::Input;
+
+fuzz_target!(|s: &str| {
+    let Ok(lab_state) = LabState::try_from(Input::from(s)) else {
+        return;
+    };
+
+    // Every visited position is a cell of the map, and so a character of the input
+    if let Ok(lab_state) = lab_state.advance_until_guard_leaves() {
+        assert!(lab_state.visited_positions() <= s.chars().count());
+    }
+});
@@ -0,0 +1,303 @@
+use std::fmt::Debug;
+
+use anyhow::{anyhow, Result};
+use log::Level::Trace;
+use log::{debug, log_enabled, trace};
+
+use util::{cycle, grid, Direction, Input, LocationContext, Part, Solution};
+
+#[derive(Copy, Clone, Debug)]
+struct GuardState {
+    direction: Direction,
+    position: (usize, usize),
+    has_left: bool,
+}
+
+impl GuardState {
+    fn new(x: usize, y: usize) -> Self {
+        Self {
+            direction: Direction::Up,
+            position: (x, y),
+            has_left: false,
+        }
+    }
+
+    fn rotate(&mut self) {
+        self.direction = match self.direction {
+            Direction::Up => Direction::Right,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+            Direction::Right => Direction::Down,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct LabState {
+    obstacles: Vec<Vec<bool>>,
+    visited: Vec<Vec<bool>>,
+    guard: GuardState,
+}
+
+impl LabState {
+    pub fn visited_positions(&self) -> usize {
+        self.visited
+            .iter()
+            .map(|r| r.iter().map(|v| if *v { 1 } else { 0 }).sum::<usize>())
+            .sum()
+    }
+
+    /// Fails if the guard is stuck in a loop, which is detected once they turn at the same
+    /// position to face the same direction twice.
+    pub fn advance_until_guard_leaves(self) -> Result<Self> {
+        let mut state = self;
+        if log_enabled!(Trace) {
+            trace!("\n{}", state.debug_current_state(true));
+        }
+
+        let mut turns = cycle::Detector::new();
+
+        while !state.guard.has_left {
+            if let Some(cycle) = turns.push((state.guard.position, state.guard.direction)) {
+                debug!("Guard loops every {} turns", cycle.length);
+                return Err(anyhow!("Guard is stuck in a loop"));
+            }
+
+            state = state.advance()?;
+            if log_enabled!(Trace) {
+                trace!("\n{}", state.debug_current_state(true));
+            }
+        }
+        Ok(state)
+    }
+
+    fn advance(self) -> Result<Self> {
+        if self.guard.has_left {
+            return Err(anyhow!("Guard has left"));
+        }
+
+        let obstacles = self.obstacles;
+        let mut visited = self.visited;
+        let mut guard = self.guard;
+
+        let start = (
+            i64::try_from(guard.position.0)?,
+            i64::try_from(guard.position.1)?,
+        );
+        let path = grid::ray(&obstacles, start, guard.direction).cells();
+
+        // Walks until the next obstacle, or off the edge of the lab if there isn't one
+        guard.has_left = true;
+        for ((x, y), &obstacle) in path {
+            if obstacle {
+                guard.rotate();
+                guard.has_left = false;
+                break;
+            }
+
+            guard.position = (usize::try_from(x)?, usize::try_from(y)?);
+            visited[guard.position.1][guard.position.0] = true;
+        }
+
+        Ok(Self {
+            obstacles,
+            visited,
+            guard,
+        })
+    }
+
+    fn debug_current_state(&self, show_path: bool) -> String {
+        let mut lines = Vec::with_capacity(self.obstacles.len());
+
+        for y in 0..self.obstacles.len() {
+            let mut line = String::new();
+            for x in 0..self.obstacles[y].len() {
+                if self.obstacles[y][x] {
+                    line.push('#');
+                } else if self.guard.position == (x, y) {
+                    line.push(char::from(self.guard.direction));
+                } else if show_path && self.visited[y][x] {
+                    line.push('X');
+                } else {
+                    line.push('.');
+                }
+            }
+            lines.push(line);
+        }
+
+        lines.join("\n")
+    }
+}
+
+impl Solution for LabState {
+    type Answer = usize;
+
+    fn parse(input: Input) -> Result<Self> {
+        Self::try_from(input)
+    }
+
+    fn solve(&self, part: Part) -> Result<usize> {
+        match part {
+            Part::One => Ok(self
+                .clone()
+                .advance_until_guard_leaves()?
+                .visited_positions()),
+            Part::Two => Err(anyhow!("Part 2 isn't implemented yet")),
+        }
+    }
+}
+
+impl TryFrom<Input> for LabState {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Input) -> Result<Self, Self::Error> {
+        let mut obstacles = Vec::new();
+        let mut visited = Vec::new();
+
+        let mut guard = None;
+
+        for (y, line) in value.lines().enumerate() {
+            let mut obstacles_row = vec![false; line.text.len()];
+            let mut visited_row = vec![false; line.text.len()];
+
+            for (x, c) in line.text.chars().enumerate() {
+                match c {
+                    '.' => (),
+                    '#' => obstacles_row[x] = true,
+                    '^' => {
+                        visited_row[x] = true;
+                        guard = Some(GuardState::new(x, y));
+                    }
+                    _ => {
+                        return Err(anyhow!("Invalid character in grid: {c}"))
+                            .at(line.number, x + 1)
+                    }
+                }
+            }
+
+            if let Some(width) = obstacles.first().map(Vec::len) {
+                if obstacles_row.len() != width {
+                    return Err(anyhow!(
+                        "Expected {width} cells but found {}",
+                        obstacles_row.len()
+                    ))
+                    .at_line(line.number);
+                }
+            }
+
+            obstacles.push(obstacles_row);
+            visited.push(visited_row);
+        }
+
+        Ok(LabState {
+            obstacles,
+            visited,
+            guard: guard.ok_or_else(|| anyhow!("Guard not found"))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use util::generate::{self, GuardFate};
+    use util::{arbitrary_input_test, example_test, generated_test};
+
+    example_test!(part_1_example, LabState, Part::One, 41);
+
+    #[test]
+    fn invalid_character() {
+        let err = LabState::try_from(Input::from("..#\n.^x")).err().unwrap();
+        assert_eq!(
+            "line 2, column 3: Invalid character in grid: x",
+            format!("{err:#}")
+        );
+    }
+
+    example_test!(
+        #[ignore]
+        part_2_example,
+        LabState,
+        Part::Two,
+        6
+    );
+
+    #[test]
+    fn invalid_grids() {
+        let err = LabState::try_from(Input::from("..#\n.^")).err().unwrap();
+        assert_eq!("line 2: Expected 3 cells but found 2", format!("{err:#}"));
+
+        let err = LabState::try_from(Input::from(".#.\n#^#\n.#."))
+            .and_then(LabState::advance_until_guard_leaves)
+            .err()
+            .unwrap();
+        assert_eq!("Guard is stuck in a loop", err.to_string());
+    }
+
+    fn labs() -> impl Strategy<Value = String> {
+        (1..10usize, 1..10usize)
+            .prop_flat_map(|(width, height)| {
+                (
+                    prop::collection::vec(prop::bool::weighted(0.2), width * height),
+                    0..width * height,
+                    Just(width),
+                )
+            })
+            .prop_map(|(obstacles, guard, width)| {
+                obstacles
+                    .chunks(width)
+                    .enumerate()
+                    .map(|(y, row)| {
+                        row.iter()
+                            .enumerate()
+                            .map(|(x, obstacle)| match (y * width + x == guard, obstacle) {
+                                (true, _) => '^',
+                                (false, true) => '#',
+                                (false, false) => '.',
+                            })
+                            .collect::<String>()
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn generated_labs(input in labs()) {
+            let lab_state = LabState::try_from(Input::from(input.as_str())).unwrap();
+            prop_assert_eq!(
+                input.replace('^', &char::from(Direction::Up).to_string()),
+                lab_state.debug_current_state(false)
+            );
+
+            if let Ok(lab_state) = lab_state.advance_until_guard_leaves() {
+                prop_assert!(lab_state.visited_positions() >= 1);
+            }
+        }
+    }
+
+    generated_test!(generated_input, LabState, Part::One, |rng| {
+        let (input, visited) = generate::lab(rng, 30, GuardFate::Exits);
+        (input, visited.expect("the guard exits"))
+    });
+
+    #[test]
+    fn generated_loop() -> Result<()> {
+        let (input, _) = generate::lab(&mut generate::Rng::new(6), 30, GuardFate::Loops);
+        assert!(LabState::try_from(Input::from(input))?
+            .advance_until_guard_leaves()
+            .is_err());
+
+        Ok(())
+    }
+
+    arbitrary_input_test!(
+        arbitrary_input_does_not_panic,
+        LabState,
+        "[.#^\n]{0,64}|\\PC{0,64}"
+    );
+}
@@ -0,0 +1,19 @@
+#![no_main]
+
+use std::str::FromStr;
+
+use libfuzzer_sys::fuzz_target;
+
+use day07::{CalibrationEquation, Calibrations};
+use util::memo::Memo;
+use util::Input;
+
+fuzz_target!(|s: &str| {
+    if let Ok(equation) = CalibrationEquation::from_str(s) {
+        let _ = equation.is_valid(&mut Memo::new());
+    }
+
+    if let Ok(calibrations) = Calibrations::try_from(Input::from(s)) {
+        let _ = calibrations.result();
+    }
+});
@@ -0,0 +1,309 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use log::{debug, trace};
+
+use util::memo::Memo;
+use util::{parse, Input, LocationContext, Part, Solution};
+
+#[derive(Debug)]
+pub struct Calibrations(Vec<CalibrationEquation>);
+
+impl Calibrations {
+    pub fn result(&self) -> Result<usize> {
+        let mut memo = Memo::new();
+
+        let result = self
+            .0
+            .iter()
+            .filter(|c| c.is_valid(&mut memo))
+            .try_fold(0usize, |sum, c| sum.checked_add(c.test))
+            .ok_or_else(|| anyhow!("Result overflowed"));
+
+        debug!("Validity cache: {}", memo.stats());
+        result
+    }
+}
+
+impl TryFrom<Input> for Calibrations {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Input) -> Result<Self, Self::Error> {
+        let mut equations = Vec::with_capacity(value.len());
+        for line in value.lines() {
+            equations.push(CalibrationEquation::from_str(line.text).at_line(line.number)?);
+        }
+
+        Ok(Self(equations))
+    }
+}
+
+#[derive(Debug)]
+pub struct CalibrationEquation {
+    test: usize,
+    numbers: Vec<usize>,
+}
+
+impl CalibrationEquation {
+    /// Whether `test` can be made from `numbers`, cached by `test` and how many numbers are left,
+    /// since `+` and `*` can reach the same value by different routes, e.g. with 1s and 2s.
+    fn inner_is_valid(
+        memo: &mut Memo<(usize, usize), bool>,
+        test: usize,
+        numbers: &[usize],
+    ) -> bool {
+        memo.get_or_compute((test, numbers.len()), |memo| {
+            if numbers.is_empty() {
+                return false;
+            }
+
+            // The first number only matches the test once every other number has been used
+            if numbers.len() == 1 {
+                return test == numbers[0];
+            }
+
+            let curr = *numbers.last().unwrap();
+            let remaining = &numbers[..numbers.len() - 1];
+
+            trace!("{curr} {remaining:?}");
+
+            let addition = if let Some(test) = test.checked_sub(curr) {
+                Self::inner_is_valid(memo, test, remaining)
+            } else {
+                false
+            };
+
+            let multiplication = match curr {
+                // Multiplying anything by zero makes zero
+                0 => test == 0,
+                _ if test.is_multiple_of(curr) => {
+                    Self::inner_is_valid(memo, test / curr, remaining)
+                }
+                _ => false,
+            };
+
+            addition || multiplication
+        })
+    }
+
+    /// Clears `memo` first, since its keys only make sense for one equation's numbers, reusing it
+    /// to keep its stats across equations.
+    pub fn is_valid(&self, memo: &mut Memo<(usize, usize), bool>) -> bool {
+        memo.clear();
+        Self::inner_is_valid(memo, self.test, &self.numbers)
+    }
+}
+
+impl FromStr for CalibrationEquation {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (test, numbers) = parse::key_list(s, ":")?;
+        Ok(Self { test, numbers })
+    }
+}
+
+impl Solution for Calibrations {
+    type Answer = usize;
+
+    fn parse(input: Input) -> Result<Self> {
+        Self::try_from(input)
+    }
+
+    fn solve(&self, part: Part) -> Result<usize> {
+        match part {
+            Part::One => self.result(),
+            Part::Two => Err(anyhow!("Part 2 isn't implemented yet")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use util::{arbitrary_input_test, differential, example_test, generate, generated_test};
+
+    example_test!(part_1_answer, Calibrations, Part::One, 3749);
+    example_test!(
+        #[ignore]
+        part_2_answer,
+        Calibrations,
+        Part::Two,
+        11387
+    );
+
+    #[test]
+    fn part_1_example() -> Result<()> {
+        let input = util::init_test()?;
+        let equations = Calibrations::try_from(input)?;
+
+        for equation in equations.0.iter() {
+            assert_eq!(
+                equation.test == 190 || equation.test == 3267 || equation.test == 292,
+                equation.is_valid(&mut Memo::new()),
+                "failed on {equation:?}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    #[ignore]
+    fn part_2_example() -> Result<()> {
+        let input = util::init_test()?;
+        let equations = Calibrations::try_from(input)?;
+
+        let equation = CalibrationEquation {
+            test: 7290,
+            numbers: vec![6, 8, 6, 15],
+        };
+        assert!(equation.is_valid(&mut Memo::new()));
+
+        for equation in equations.0.iter() {
+            assert_eq!(
+                equation.test == 190
+                    || equation.test == 3267
+                    || equation.test == 292
+                    || equation.test == 156
+                    || equation.test == 7290
+                    || equation.test == 192,
+                equation.is_valid(&mut Memo::new()),
+                "failed on {equation:?}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn overflow() -> Result<()> {
+        let input = format!("{0}: {0}\n{0}: {0}", usize::MAX);
+        assert!(Calibrations::try_from(Input::from(input))?
+            .result()
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn every_number_is_used() -> Result<()> {
+        assert!(!CalibrationEquation::from_str("6: 2 3 4")?.is_valid(&mut Memo::new()));
+        assert!(!CalibrationEquation::from_str("5: 5 2")?.is_valid(&mut Memo::new()));
+        assert!(CalibrationEquation::from_str("5: 5 1")?.is_valid(&mut Memo::new()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn multiplication_by_zero() -> Result<()> {
+        assert!(CalibrationEquation::from_str("0: 5 0")?.is_valid(&mut Memo::new()));
+        assert!(CalibrationEquation::from_str("5: 5 0")?.is_valid(&mut Memo::new()));
+        assert!(!CalibrationEquation::from_str("3: 5 0")?.is_valid(&mut Memo::new()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn memo_reuses_subproblems() -> Result<()> {
+        // 4 - 2 and 4 / 2 both leave 2 to be made from the first two numbers
+        let mut memo = Memo::new();
+        assert!(!CalibrationEquation::from_str("4: 9 2 2")?.is_valid(&mut memo));
+        assert_eq!(1, memo.stats().hits);
+
+        Ok(())
+    }
+
+    /// Tries every combination of operators, evaluated left to right.
+    fn reference_is_valid(equation: &CalibrationEquation) -> bool {
+        let numbers = &equation.numbers;
+        (0..1u64 << (numbers.len() - 1)).any(|operators| {
+            numbers[1..]
+                .iter()
+                .enumerate()
+                .try_fold(numbers[0], |acc, (i, n)| match operators >> i & 1 {
+                    1 => acc.checked_mul(*n),
+                    _ => acc.checked_add(*n),
+                })
+                == Some(equation.test)
+        })
+    }
+
+    #[test]
+    fn matches_reference() -> Result<()> {
+        // Small numbers and tests, so that both valid and invalid equations are common
+        let generate = |rng: &mut generate::Rng| {
+            (0..rng.range(1, 10))
+                .map(|_| {
+                    let numbers = (0..rng.range(1, 6))
+                        .map(|_| rng.range(0, 6).to_string())
+                        .collect::<Vec<_>>();
+                    format!("{}: {}", rng.range(0, 40), numbers.join(" "))
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let parse = |input: &Input| Calibrations::try_from(input.clone());
+
+        differential::compare(
+            500,
+            generate,
+            |input| parse(input)?.result(),
+            |input| {
+                Ok(parse(input)?
+                    .0
+                    .iter()
+                    .filter(|c| reference_is_valid(c))
+                    .map(|c| c.test)
+                    .sum())
+            },
+        )?;
+        differential::compare(
+            20,
+            |rng| generate::calibrations(rng, 100, 0.5).0,
+            |input| parse(input)?.result(),
+            |input| {
+                Ok(parse(input)?
+                    .0
+                    .iter()
+                    .filter(|c| reference_is_valid(c))
+                    .map(|c| c.test)
+                    .sum())
+            },
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn generated_equations_are_valid(
+            numbers in prop::collection::vec(0..20usize, 1..8),
+            multiply in prop::collection::vec(any::<bool>(), 8),
+        ) {
+            let test = numbers[1..]
+                .iter()
+                .zip(multiply)
+                .fold(numbers[0], |acc, (n, multiply)| if multiply { acc * n } else { acc + n });
+
+            let line = format!(
+                "{test}: {}",
+                numbers.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(" ")
+            );
+            let equation = CalibrationEquation::from_str(&line).unwrap();
+            prop_assert_eq!(test, equation.test);
+            prop_assert_eq!(&numbers, &equation.numbers);
+            prop_assert!(equation.is_valid(&mut Memo::new()), "{:?}", equation);
+        }
+    }
+
+    generated_test!(generated_input, Calibrations, Part::One, |rng| {
+        generate::calibrations(rng, 500, 0.5)
+    });
+
+    arbitrary_input_test!(
+        arbitrary_input_does_not_panic,
+        Calibrations,
+        "[0-9: \n]{0,32}|\\PC{0,64}"
+    );
+}
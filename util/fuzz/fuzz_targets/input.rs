@@ -0,0 +1,25 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use util::Input;
+
+fuzz_target!(|s: &str| {
+    let input = Input::from(s);
+
+    let lines = input.lines().collect::<Vec<_>>();
+    assert_eq!(input.len(), lines.len());
+    assert_eq!(lines.len(), input.grid().len());
+
+    for (i, line) in lines.iter().enumerate() {
+        assert_eq!(i + 1, line.number);
+        if let Some(part) = line.text.split_whitespace().last() {
+            assert!(line.column_of(part).is_some_and(|c| c <= line.text.len()));
+        }
+    }
+
+    for paragraph in input.paragraphs() {
+        assert!(!paragraph.is_empty());
+        assert!(paragraph.iter().all(|l| !l.text.trim().is_empty()));
+    }
+});
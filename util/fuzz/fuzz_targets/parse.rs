@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use util::{parse, Input};
+
+fuzz_target!(|s: &str| {
+    let _ = parse::number::<i64>(s);
+    let _ = parse::numbers::<usize>(s);
+    let _ = parse::integers::<i32>(s);
+    let _ = parse::separated_pair::<usize, usize>(s, " ");
+    let _ = parse::separated_pair::<u8, i16>(s, "|");
+    let _ = parse::key_list::<usize, usize>(s, ":");
+    let _ = parse::grid(Input::from(s).lines(), Ok);
+});
@@ -0,0 +1,477 @@
+use std::fmt::Display;
+use std::fs::File;
+use std::io::BufReader;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use env_logger::Target;
+use log::LevelFilter;
+
+use crate::generate::Rng;
+use crate::{Input, ReadOptions, Reporter, INPUT_PATH, TEST_INPUT_PATH};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputType {
+    Test,
+    Actual,
+}
+
+impl FromStr for InputType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "test" => Ok(Self::Test),
+            "actual" => Ok(Self::Actual),
+            _ => Err(anyhow!("Invalid input type: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::One => write!(f, "1"),
+            Self::Two => write!(f, "2"),
+        }
+    }
+}
+
+/// Which parts of a day to run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parts {
+    One,
+    Two,
+    Both,
+}
+
+impl Parts {
+    pub fn contains(&self, part: Part) -> bool {
+        matches!(
+            (self, part),
+            (Self::Both, _) | (Self::One, Part::One) | (Self::Two, Part::Two)
+        )
+    }
+}
+
+impl FromStr for Parts {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "1" => Ok(Self::One),
+            "2" => Ok(Self::Two),
+            "both" => Ok(Self::Both),
+            _ => Err(anyhow!("Invalid part: {}", s)),
+        }
+    }
+}
+
+/// How answers are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Ndjson,
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "ndjson" => Ok(Self::Ndjson),
+            _ => Err(anyhow!("Invalid format: {}", s)),
+        }
+    }
+}
+
+/// Generates an input from a seeded [`Rng`] and a size.
+pub type Generator = fn(&mut Rng, usize, &ArgMatches) -> Result<String>;
+
+/// Builds the command line shared by every day, plus any day-specific arguments.
+#[derive(Debug)]
+pub struct Cli {
+    day: u32,
+    args: Vec<Arg>,
+    options: ReadOptions,
+    generator: Option<(Vec<Arg>, Generator)>,
+}
+
+impl Cli {
+    pub fn new(day: u32) -> Self {
+        Self {
+            day,
+            args: Vec::new(),
+            options: ReadOptions::default(),
+            generator: None,
+        }
+    }
+
+    pub fn arg(mut self, arg: Arg) -> Self {
+        self.args.push(arg);
+        self
+    }
+
+    pub fn args(mut self, args: impl IntoIterator<Item = Arg>) -> Self {
+        self.args.extend(args);
+        self
+    }
+
+    /// Adds a `gen` subcommand that prints a generated input instead of solving.
+    pub fn generator(mut self, args: impl IntoIterator<Item = Arg>, generator: Generator) -> Self {
+        self.generator = Some((args.into_iter().collect(), generator));
+        self
+    }
+
+    /// Accept an input with no content, see [`ReadOptions::allow_empty`].
+    pub fn allow_empty(mut self) -> Self {
+        self.options.allow_empty = true;
+        self
+    }
+
+    /// Parses the command line and reads the selected input, or generates one for `gen`.
+    pub fn init(self) -> Result<Parsed<Input>> {
+        let options = self.options;
+        let mut config = match self.parse()? {
+            Parsed::Solve(config, ()) => config,
+            Parsed::Generated(input) => return Ok(Parsed::Generated(input)),
+        };
+
+        let options = ReadOptions {
+            lossy: options.lossy || config.matches.get_flag("lossy"),
+            ..options
+        };
+
+        let input = crate::read_input(config.input_path(), options)?;
+        config.hash_input()?;
+
+        Ok(Parsed::Solve(config, input))
+    }
+
+    /// Like [`Cli::init`], but returns a reader over the input rather than reading it all, for
+    /// inputs too large to hold in memory.
+    pub fn init_reader(self) -> Result<Parsed<BufReader<File>>> {
+        let mut config = match self.parse()? {
+            Parsed::Solve(config, ()) => config,
+            Parsed::Generated(input) => return Ok(Parsed::Generated(input)),
+        };
+        let reader = crate::open(config.input_path())?;
+        config.hash_input()?;
+
+        Ok(Parsed::Solve(config, reader))
+    }
+
+    fn command(&mut self) -> Command {
+        let command = Command::new("advent-of-code")
+            .arg(
+                Arg::new("verbose")
+                    .short('v')
+                    .long("verbose")
+                    .action(ArgAction::SetTrue)
+                    .help("increase log level from the default for the input type"),
+            )
+            .arg(
+                Arg::new("quiet")
+                    .short('q')
+                    .long("quiet")
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with_all(["verbose", "log-level"])
+                    .help("disable logging, only printing answers"),
+            )
+            .arg(
+                Arg::new("log-level")
+                    .long("log-level")
+                    .conflicts_with("verbose")
+                    .help("log level, overriding the default for the input type"),
+            )
+            .arg(
+                Arg::new("input")
+                    .short('i')
+                    .long("input")
+                    .default_value("actual")
+                    .help(format!(
+                        "input type, {:?} or {:?}",
+                        InputType::Test,
+                        InputType::Actual
+                    )),
+            )
+            .arg(
+                Arg::new("example")
+                    .short('e')
+                    .long("example")
+                    .conflicts_with("input")
+                    .help("use the Nth example input, implies the test input type"),
+            )
+            .arg(
+                Arg::new("part")
+                    .short('p')
+                    .long("part")
+                    .default_value("both")
+                    .help("part to run, 1, 2 or both"),
+            )
+            .arg(
+                Arg::new("format")
+                    .long("format")
+                    .default_value("text")
+                    .help(format!(
+                        "answer format, {:?}, {:?} or {:?}",
+                        Format::Text,
+                        Format::Json,
+                        Format::Ndjson
+                    )),
+            )
+            .arg(
+                Arg::new("lossy")
+                    .long("lossy")
+                    .action(ArgAction::SetTrue)
+                    .help("replace invalid UTF-8 in the input rather than failing"),
+            )
+            .args(self.args.drain(..));
+
+        match &mut self.generator {
+            Some((args, _)) => {
+                command.subcommand(
+                    Command::new("gen")
+                        .about("print a generated input instead of solving")
+                        .arg(Arg::new("size").long("size").default_value("1000").help(
+                            "size of the input, e.g. lines or grid width, depending on the day",
+                        ))
+                        .arg(
+                            Arg::new("seed")
+                                .long("seed")
+                                .default_value("0")
+                                .help("seed for the random number generator"),
+                        )
+                        .args(args.drain(..)),
+                )
+            }
+            None => command,
+        }
+    }
+
+    fn parse(mut self) -> Result<Parsed<()>> {
+        let matches = self.command().get_matches();
+        let parsed = self.parse_matches(matches)?;
+        if let Parsed::Solve(config, ()) = &parsed {
+            crate::init_logger(config.log_level, config.log_target())?;
+        }
+        Ok(parsed)
+    }
+
+    fn parse_matches(self, matches: ArgMatches) -> Result<Parsed<()>> {
+        if let (Some((_, generator)), Some(("gen", matches))) =
+            (self.generator, matches.subcommand())
+        {
+            let get = |name: &str| {
+                let value = matches
+                    .get_one::<String>(name)
+                    .ok_or_else(|| anyhow!("Missing {name}"))?;
+                u64::from_str(value).map_err(|_| anyhow!("Invalid {name}: {}", value))
+            };
+
+            let mut rng = Rng::new(get("seed")?);
+            let input = generator(&mut rng, get("size")? as usize, matches)?;
+            return Ok(Parsed::Generated(input));
+        }
+
+        Ok(Parsed::Solve(Config::new(self.day, matches)?, ()))
+    }
+}
+
+#[derive(Debug)]
+pub enum Parsed<T> {
+    Solve(Config, T),
+    Generated(String),
+}
+
+/// The parsed command line.
+#[derive(Debug)]
+pub struct Config {
+    pub day: u32,
+    /// Matches for all arguments, including day-specific ones.
+    pub matches: ArgMatches,
+    pub input: InputType,
+    pub log_level: LevelFilter,
+    /// Which example to use for the test input, starting from 1.
+    pub example: usize,
+    pub parts: Parts,
+    pub format: Format,
+    /// SHA-256 of the input file, only calculated for the machine-readable formats.
+    pub input_hash: Option<String>,
+}
+
+impl Config {
+    pub fn new(day: u32, matches: ArgMatches) -> Result<Self> {
+        let get = |name: &str| {
+            matches
+                .get_one::<String>(name)
+                .ok_or_else(|| anyhow!("Missing {name}"))
+        };
+
+        let example = matches
+            .get_one::<String>("example")
+            .map(|s| match usize::from_str(s) {
+                Ok(n) if n > 0 => Ok(n),
+                _ => Err(anyhow!("Invalid example: {}", s)),
+            })
+            .transpose()?;
+
+        let input = match example {
+            Some(_) => InputType::Test,
+            None => InputType::from_str(get("input")?)?,
+        };
+
+        let log_level = if matches.get_flag("quiet") {
+            LevelFilter::Off
+        } else if let Some(level) = matches.get_one::<String>("log-level") {
+            LevelFilter::from_str(level).map_err(|_| anyhow!("Invalid log level: {}", level))?
+        } else {
+            match (input, matches.get_flag("verbose")) {
+                (InputType::Actual, false) => LevelFilter::Info,
+                (InputType::Actual, true) => LevelFilter::Debug,
+                (InputType::Test, false) => LevelFilter::Debug,
+                (InputType::Test, true) => LevelFilter::Trace,
+            }
+        };
+
+        Ok(Self {
+            day,
+            input,
+            log_level,
+            example: example.unwrap_or(1),
+            parts: Parts::from_str(get("part")?)?,
+            format: Format::from_str(get("format")?)?,
+            input_hash: None,
+            matches,
+        })
+    }
+
+    pub fn runs(&self, part: Part) -> bool {
+        self.parts.contains(part)
+    }
+
+    /// A [`Reporter`] for the selected parts and format.
+    pub fn reporter(&self) -> Reporter {
+        Reporter::new(self.day, self.parts, self.format, self.input_hash.clone())
+    }
+
+    /// Logs go to stderr for the machine-readable formats, so that stdout only holds records.
+    fn log_target(&self) -> Target {
+        match self.format {
+            Format::Text => Target::Stdout,
+            Format::Json | Format::Ndjson => Target::Stderr,
+        }
+    }
+
+    fn hash_input(&mut self) -> Result<()> {
+        if self.format != Format::Text {
+            self.input_hash = Some(crate::report::hash_file(self.input_path())?);
+        }
+        Ok(())
+    }
+
+    fn input_path(&self) -> String {
+        match (self.input, self.example) {
+            (InputType::Actual, _) => INPUT_PATH.to_string(),
+            (InputType::Test, 1) => TEST_INPUT_PATH.to_string(),
+            (InputType::Test, n) => format!("{TEST_INPUT_PATH}{n}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Config> {
+        let command = Cli::new(1).arg(Arg::new("extra").long("extra")).command();
+        Config::new(
+            1,
+            command.try_get_matches_from(
+                std::iter::once("advent-of-code").chain(args.iter().copied()),
+            )?,
+        )
+    }
+
+    #[test]
+    fn test_config() -> Result<()> {
+        let config = parse(&[])?;
+        assert_eq!(1, config.day);
+        assert_eq!(InputType::Actual, config.input);
+        assert_eq!(LevelFilter::Info, config.log_level);
+        assert_eq!(Parts::Both, config.parts);
+        assert_eq!(Format::Text, config.format);
+        assert_eq!(INPUT_PATH, config.input_path());
+
+        let config = parse(&["--part", "2", "--format", "ndjson", "--extra", "x"])?;
+        assert!(!config.runs(Part::One));
+        assert!(config.runs(Part::Two));
+        assert_eq!(Format::Ndjson, config.format);
+        assert_eq!(
+            Some(&"x".to_string()),
+            config.matches.get_one::<String>("extra")
+        );
+
+        let config = parse(&["-e", "2", "-v"])?;
+        assert_eq!(InputType::Test, config.input);
+        assert_eq!(LevelFilter::Trace, config.log_level);
+        assert_eq!("input/example2", config.input_path());
+
+        assert_eq!(LevelFilter::Off, parse(&["-q"])?.log_level);
+        assert_eq!(
+            LevelFilter::Warn,
+            parse(&["--log-level", "warn"])?.log_level
+        );
+
+        assert!(parse(&["-e", "0"]).is_err());
+        assert!(parse(&["--part", "3"]).is_err());
+        assert!(parse(&["-q", "-v"]).is_err());
+        assert!(parse(&["-e", "2", "-i", "actual"]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generator() -> Result<()> {
+        let generate = |args: &[&str]| {
+            let mut cli = Cli::new(1).generator([Arg::new("unit").long("unit")], |rng, size, m| {
+                let unit = m.get_one::<String>("unit").map_or("x", String::as_str);
+                Ok(format!("{size}{unit} {}", rng.range(0, 1000)))
+            });
+            let matches = cli.command().try_get_matches_from(
+                std::iter::once("advent-of-code").chain(args.iter().copied()),
+            )?;
+            cli.parse_matches(matches)
+        };
+        let generated = |args: &[&str]| match generate(args)? {
+            Parsed::Generated(input) => Ok(input),
+            Parsed::Solve(..) => Err(anyhow!("Expected a generated input")),
+        };
+
+        let input = generated(&["gen", "--size", "3", "--seed", "7", "--unit", "m"])?;
+        assert!(input.starts_with("3m "), "{input}");
+        assert_eq!(
+            input,
+            generated(&["gen", "--seed", "7", "--size", "3", "--unit", "m"])?
+        );
+        assert!(generated(&["gen"])?.starts_with("1000x "));
+
+        match generate(&["-q"])? {
+            Parsed::Solve(config, ()) => assert_eq!(LevelFilter::Off, config.log_level),
+            Parsed::Generated(_) => return Err(anyhow!("Expected a config")),
+        }
+        assert!(generate(&["gen", "--size", "-1"]).is_err());
+
+        Ok(())
+    }
+}
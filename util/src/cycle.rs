@@ -0,0 +1,232 @@
+//! Finding where a sequence of states starts repeating, and using that to skip ahead to far later
+//! states. Sequences are given by a starting state and a `step` to the next, which returns `None`
+//! if the sequence ends, in which case there's no cycle.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Where a sequence starts repeating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// Count of states before the first one that's repeated.
+    pub prefix: usize,
+    /// Count of states in each repetition.
+    pub length: usize,
+}
+
+/// Floyd's tortoise and hare, which keeps only two states at a time but steps through the sequence
+/// about three times over.
+pub fn floyd<S: Clone + Eq>(start: S, mut step: impl FnMut(&S) -> Option<S>) -> Option<Cycle> {
+    let mut tortoise = step(&start)?;
+    let mut hare = step(&tortoise)?;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        let next = step(&hare)?;
+        hare = step(&next)?;
+    }
+
+    let mut prefix = 0;
+    tortoise = start;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        prefix += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise)?;
+    while tortoise != hare {
+        hare = step(&hare)?;
+        length += 1;
+    }
+
+    Some(Cycle { prefix, length })
+}
+
+/// Brent's algorithm, which keeps only two states at a time like [`floyd`] but usually takes fewer
+/// steps.
+pub fn brent<S: Clone + Eq>(start: S, mut step: impl FnMut(&S) -> Option<S>) -> Option<Cycle> {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start)?;
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare)?;
+        length += 1;
+    }
+
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..length {
+        hare = step(&hare)?;
+    }
+
+    let mut prefix = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        prefix += 1;
+    }
+
+    Some(Cycle { prefix, length })
+}
+
+/// Remembers every state it's given to spot the first repeat, stepping through the sequence only
+/// once at the cost of memory.
+#[derive(Debug, Clone)]
+pub struct Detector<S> {
+    seen: HashMap<S, usize>,
+    steps: usize,
+}
+
+impl<S: Hash + Eq> Detector<S> {
+    pub fn new() -> Self {
+        Self {
+            seen: HashMap::new(),
+            steps: 0,
+        }
+    }
+
+    /// Adds the next state in the sequence, returning the cycle if it's been seen before.
+    pub fn push(&mut self, state: S) -> Option<Cycle> {
+        let steps = self.steps;
+        self.steps += 1;
+
+        match self.seen.entry(state) {
+            Entry::Occupied(e) => Some(Cycle {
+                prefix: *e.get(),
+                length: steps - e.get(),
+            }),
+            Entry::Vacant(e) => {
+                e.insert(steps);
+                None
+            }
+        }
+    }
+}
+
+impl<S: Hash + Eq> Default for Detector<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The first cycle in `states`, by hashing, see [`Detector`].
+pub fn detect<S: Hash + Eq>(states: impl IntoIterator<Item = S>) -> Option<Cycle> {
+    let mut detector = Detector::new();
+    states.into_iter().find_map(|s| detector.push(s))
+}
+
+/// The state `n` steps after `start`, stepping only until the sequence repeats and working out
+/// where in the cycle step `n` lands. `None` if the sequence ends first.
+pub fn fast_forward<S: Clone + Hash + Eq>(
+    start: S,
+    mut step: impl FnMut(&S) -> Option<S>,
+    n: usize,
+) -> Option<S> {
+    let mut detector = Detector::new();
+    let mut states: Vec<S> = Vec::new();
+
+    let mut state = start;
+    while states.len() < n {
+        if let Some(Cycle { prefix, length }) = detector.push(state.clone()) {
+            return Some(states[prefix + (n - prefix) % length].clone());
+        }
+
+        let next = step(&state)?;
+        states.push(state);
+        state = next;
+    }
+
+    Some(state)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A sequence with a prefix and cycle length depending on `modulus` and `start`.
+    fn step(modulus: u64) -> impl Fn(&u64) -> Option<u64> {
+        move |x| Some((x * x + 1) % modulus)
+    }
+
+    fn naive(start: u64, step: impl Fn(&u64) -> Option<u64>) -> Cycle {
+        let mut states = vec![start];
+        loop {
+            let next = step(states.last().unwrap()).unwrap();
+            if let Some(prefix) = states.iter().position(|&s| s == next) {
+                return Cycle {
+                    prefix,
+                    length: states.len() - prefix,
+                };
+            }
+            states.push(next);
+        }
+    }
+
+    #[test]
+    fn test_algorithms_agree() {
+        for modulus in [1, 2, 7, 100, 255, 1009, 65537] {
+            for start in [0, 3, 42] {
+                let expected = naive(start, step(modulus));
+
+                assert_eq!(
+                    Some(expected),
+                    floyd(start, step(modulus)),
+                    "{modulus} {start}"
+                );
+                assert_eq!(
+                    Some(expected),
+                    brent(start, step(modulus)),
+                    "{modulus} {start}"
+                );
+                assert_eq!(
+                    Some(expected),
+                    detect(std::iter::successors(Some(start), step(modulus))),
+                    "{modulus} {start}"
+                );
+            }
+        }
+
+        // 0, 1, 2, 3, 1, 2, 3, ...
+        let cycle = Cycle {
+            prefix: 1,
+            length: 3,
+        };
+        let step = |&x: &u8| Some(x % 3 + 1);
+        assert_eq!(Some(cycle), floyd(0, step));
+        assert_eq!(Some(cycle), brent(0, step));
+        assert_eq!(Some(cycle), detect([0, 1, 2, 3, 1, 2, 3]));
+    }
+
+    #[test]
+    fn test_no_cycle() {
+        let step = |&x: &u32| (x < 100).then_some(x + 1);
+        assert_eq!(None, floyd(0, step));
+        assert_eq!(None, brent(0, step));
+        assert_eq!(None, detect(0..100));
+    }
+
+    #[test]
+    fn test_fast_forward() {
+        for n in 0..500 {
+            let expected = std::iter::successors(Some(5), step(1009)).nth(n);
+            assert_eq!(expected, fast_forward(5, step(1009), n), "{n}");
+        }
+
+        // 0, 1, 2, 3, 1, 2, 3, ... reaches 3 after every multiple of 3 steps
+        let step = |&x: &u8| Some(x % 3 + 1);
+        assert_eq!(Some(3), fast_forward(0, step, 3_000_000_000_000));
+        assert_eq!(Some(1), fast_forward(0, step, 3_000_000_000_001));
+
+        let ends = |&x: &u32| (x < 10).then_some(x + 1);
+        assert_eq!(Some(10), fast_forward(0, ends, 10));
+        assert_eq!(None, fast_forward(0, ends, 11));
+    }
+}
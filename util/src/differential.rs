@@ -0,0 +1,103 @@
+//! Differential testing of a solution against a simpler reference implementation, on generated
+//! inputs. The first input they disagree on is shrunk to as few lines as still disagree.
+
+use std::fmt::Debug;
+
+use anyhow::{anyhow, Result};
+
+use crate::generate::Rng;
+use crate::Input;
+
+/// Runs `solve` and `reference` on inputs generated from seeds `0..cases`, failing with the first
+/// input they disagree on, minimized. Both failing counts as agreement.
+pub fn compare<A: PartialEq + Debug>(
+    cases: u64,
+    generate: impl Fn(&mut Rng) -> String,
+    solve: impl Fn(&Input) -> Result<A>,
+    reference: impl Fn(&Input) -> Result<A>,
+) -> Result<()> {
+    let diverges = |input: &Input| solve(input).ok() != reference(input).ok();
+
+    for seed in 0..cases {
+        let input = Input::from(generate(&mut Rng::new(seed)));
+
+        if diverges(&input) {
+            let input = minimize(&input, diverges);
+            return Err(anyhow!(
+                "Solution and reference disagree for seed {seed}, {:?} != {:?}, on:\n{}",
+                solve(&input).ok(),
+                reference(&input).ok(),
+                input.raw()
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Removes chunks of lines, halving the chunk size whenever none can be removed, for as long as
+/// `diverges` still holds.
+fn minimize(input: &Input, diverges: impl Fn(&Input) -> bool) -> Input {
+    let mut lines = input.lines().map(|l| l.text).collect::<Vec<_>>();
+
+    let mut chunk = lines.len() / 2;
+    while chunk > 0 {
+        let mut removed = false;
+
+        let mut i = 0;
+        while i < lines.len() {
+            let candidate = [&lines[..i], &lines[(i + chunk).min(lines.len())..]].concat();
+
+            if !candidate.is_empty() && diverges(&Input::from(candidate.join("\n"))) {
+                lines = candidate;
+                removed = true;
+            } else {
+                i += chunk;
+            }
+        }
+
+        if !removed {
+            chunk /= 2;
+        }
+    }
+
+    Input::from(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_compare() {
+        let generate = |rng: &mut Rng| {
+            (0..20)
+                .map(|_| rng.range(0, 100).to_string())
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let sum = |input: &Input| -> Result<usize> {
+            input.lines().map(|l| Ok(l.text.parse::<usize>()?)).sum()
+        };
+
+        assert!(compare(10, generate, sum, sum).is_ok());
+
+        // Ignores 42, which the minimized input should be reduced to
+        let buggy = |input: &Input| -> Result<usize> {
+            input
+                .lines()
+                .map(|l| l.text.parse::<usize>())
+                .filter(|n| *n.as_ref().unwrap() != 42)
+                .map(|n| Ok(n?))
+                .sum()
+        };
+        let err = compare(
+            5,
+            |rng| format!("{}\n42\n{}", generate(rng), generate(rng)),
+            sum,
+            buggy,
+        )
+        .unwrap_err();
+        assert!(err.to_string().ends_with("on:\n42"), "{err}");
+    }
+}
@@ -0,0 +1,423 @@
+use std::collections::HashSet;
+
+use crate::Direction;
+
+/// SplitMix64, so generated inputs only depend on the seed.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `[low, high)`.
+    pub fn range(&mut self, low: usize, high: usize) -> usize {
+        assert!(low < high, "Empty range {low}..{high}");
+        low + (self.next_u64() % (high - low) as u64) as usize
+    }
+
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0, items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0, i + 1));
+        }
+    }
+}
+
+pub fn locations(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            format!(
+                "{}   {}",
+                rng.range(10_000, 100_000),
+                rng.range(10_000, 100_000)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns the input along with the number of safe reports.
+pub fn reports(rng: &mut Rng, size: usize, unsafe_fraction: f64) -> (String, usize) {
+    let unsafe_count = ((size as f64) * unsafe_fraction.clamp(0.0, 1.0)).round() as usize;
+    let mut is_unsafe = (0..size).map(|i| i < unsafe_count).collect::<Vec<_>>();
+    rng.shuffle(&mut is_unsafe);
+
+    let lines = is_unsafe
+        .into_iter()
+        .map(|is_unsafe| {
+            let len = rng.range(5, 9);
+            let increasing = rng.chance(0.5);
+            let bad_step = is_unsafe.then(|| rng.range(1, len));
+
+            let mut levels = vec![rng.range(30, 70)];
+            for i in 1..len {
+                let step = match bad_step {
+                    // Too large, a plateau or a change of direction
+                    Some(bad) if bad == i => *rng.choose(&[4, 5, 6, 0, -1, -2]),
+                    _ => rng.range(1, 4) as i64,
+                };
+                let step = if increasing { step } else { -step };
+                levels.push(levels[i - 1].saturating_add_signed(step as isize));
+            }
+
+            levels
+                .iter()
+                .map(|l| l.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>();
+
+    (lines.join("\n"), size - unsafe_count)
+}
+
+/// Returns the input along with its unconditional and conditional results.
+pub fn corrupted_memory(rng: &mut Rng, size: usize) -> (String, u128, u128) {
+    const NOISE: &[char] = &[
+        '!', '@', '#', '$', '%', '^', '&', '*', '+', '?', ',', ';', ' ', '\n', '[', ']', '{', '}',
+        'x', 'y', 'z', 'w', 'h', 'e', 'r', 'f', 'i', 's',
+    ];
+    const NEAR_MISSES: &[&str] = &[
+        "mul[3,7]",
+        "mul(32,64]",
+        "mul ( 2 , 4 )",
+        "mul(1234,5)",
+        "mul(4)",
+        "mul(",
+        "don't",
+        "do(1)",
+    ];
+
+    let mut memory = String::new();
+    let (mut unconditional, mut conditional) = (0u128, 0u128);
+    let mut enabled = true;
+
+    for _ in 0..size {
+        for _ in 0..rng.range(0, 8) {
+            memory.push(*rng.choose(NOISE));
+        }
+        if rng.chance(0.2) {
+            memory.push_str(rng.choose::<&str>(NEAR_MISSES));
+            memory.push(*rng.choose(NOISE));
+        }
+
+        match rng.range(0, 10) {
+            0 => {
+                memory.push_str("do()");
+                enabled = true;
+            }
+            1 => {
+                memory.push_str("don't()");
+                enabled = false;
+            }
+            _ => {
+                let (x, y) = (rng.range(0, 1000), rng.range(0, 1000));
+                memory.push_str(&format!("mul({x},{y})"));
+
+                let product = (x * y) as u128;
+                unconditional += product;
+                if enabled {
+                    conditional += product;
+                }
+            }
+        }
+    }
+
+    (memory, unconditional, conditional)
+}
+
+/// Returns the grid along with the number of times `word` appears, including across plantings.
+pub fn word_search(
+    rng: &mut Rng,
+    width: usize,
+    height: usize,
+    word: &str,
+    planted: usize,
+) -> (String, usize) {
+    let word = word.chars().collect::<Vec<_>>();
+    let filler = ('A'..='Z')
+        .filter(|c| !word.contains(c))
+        .collect::<Vec<_>>();
+
+    let mut grid = (0..height)
+        .map(|_| (0..width).map(|_| *rng.choose(&filler)).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    for _ in 0..planted {
+        let (dx, dy) = *rng.choose(&DIRECTIONS);
+        let (x, y) = (rng.range(0, width) as isize, rng.range(0, height) as isize);
+
+        let cells = (0..word.len() as isize)
+            .map(|i| (x + dx * i, y + dy * i))
+            .collect::<Vec<_>>();
+
+        let fits = cells
+            .iter()
+            .all(|&(x, y)| (0..width as isize).contains(&x) && (0..height as isize).contains(&y));
+        if fits {
+            for (&(x, y), &c) in cells.iter().zip(&word) {
+                grid[y as usize][x as usize] = c;
+            }
+        }
+    }
+
+    let count = count_word(&grid, &word);
+    let text = grid
+        .iter()
+        .map(|row| row.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    (text, count)
+}
+
+const DIRECTIONS: [(isize, isize); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+fn count_word(grid: &[Vec<char>], word: &[char]) -> usize {
+    let at = |x: isize, y: isize| {
+        usize::try_from(y)
+            .ok()
+            .and_then(|y| grid.get(y))
+            .and_then(|row| usize::try_from(x).ok().and_then(|x| row.get(x)))
+    };
+
+    let mut count = 0;
+    for y in 0..grid.len() as isize {
+        for x in 0..grid[y as usize].len() as isize {
+            for (dx, dy) in DIRECTIONS {
+                if (0..word.len() as isize)
+                    .all(|i| at(x + dx * i, y + dy * i) == Some(&word[i as usize]))
+                {
+                    count += 1;
+                }
+            }
+        }
+    }
+
+    count
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuardFate {
+    Exits,
+    Loops,
+}
+
+/// Returns the map along with how many positions the guard visits, if they exit.
+pub fn lab(rng: &mut Rng, size: usize, fate: GuardFate) -> (String, Option<usize>) {
+    let size = size.max(4);
+
+    loop {
+        let mut obstacles = (0..size)
+            .map(|_| (0..size).map(|_| rng.chance(0.1)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let guard = match fate {
+            GuardFate::Exits => (rng.range(0, size), rng.range(0, size)),
+            GuardFate::Loops => plant_loop(rng, &mut obstacles),
+        };
+        obstacles[guard.1][guard.0] = false;
+
+        let visited = walk(&obstacles, guard);
+        if visited.is_some() == (fate == GuardFate::Exits) {
+            let text = obstacles
+                .iter()
+                .enumerate()
+                .map(|(y, row)| {
+                    row.iter()
+                        .enumerate()
+                        .map(|(x, obstacle)| match (guard == (x, y), obstacle) {
+                            (true, _) => '^',
+                            (false, true) => '#',
+                            (false, false) => '.',
+                        })
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            return (text, visited);
+        }
+    }
+}
+
+fn plant_loop(rng: &mut Rng, obstacles: &mut [Vec<bool>]) -> (usize, usize) {
+    let size = obstacles.len();
+    let left = rng.range(1, size - 2);
+    let right = rng.range(left + 1, size - 1);
+    let top = rng.range(1, size - 2);
+    let bottom = rng.range(top + 1, size - 1);
+
+    obstacles[top][left..=right].fill(false);
+    obstacles[bottom][left..=right].fill(false);
+    for row in obstacles.iter_mut().take(bottom + 1).skip(top) {
+        row[left] = false;
+        row[right] = false;
+    }
+
+    obstacles[top - 1][left] = true;
+    obstacles[top][right + 1] = true;
+    obstacles[bottom + 1][right] = true;
+    obstacles[bottom][left - 1] = true;
+
+    (left, bottom)
+}
+
+fn walk(obstacles: &[Vec<bool>], (mut x, mut y): (usize, usize)) -> Option<usize> {
+    let mut direction = Direction::Up;
+    let mut visited = HashSet::from([(x, y)]);
+    let mut states = HashSet::from([(x, y, direction)]);
+
+    while let Some(neighbor) = crate::neighbor_in_direction(obstacles, direction, x, y) {
+        let (nx, ny) = neighbor.into();
+
+        if obstacles[ny][nx] {
+            direction = match direction {
+                Direction::Up => Direction::Right,
+                Direction::Right => Direction::Down,
+                Direction::Down => Direction::Left,
+                _ => Direction::Up,
+            };
+        } else {
+            (x, y) = (nx, ny);
+            visited.insert((x, y));
+        }
+
+        if !states.insert((x, y, direction)) {
+            return None;
+        }
+    }
+
+    Some(visited.len())
+}
+
+/// Returns the input along with the sum of the valid equations' test values.
+pub fn calibrations(rng: &mut Rng, size: usize, valid_fraction: f64) -> (String, usize) {
+    let mut lines = Vec::with_capacity(size);
+    let mut result = 0;
+
+    while lines.len() < size {
+        let numbers = (0..rng.range(2, 8))
+            .map(|_| rng.range(1, 100))
+            .collect::<Vec<_>>();
+
+        let mut test = numbers[0];
+        for n in &numbers[1..] {
+            test = if rng.chance(0.5) { test * n } else { test + n };
+        }
+
+        let valid = rng.chance(valid_fraction);
+        if !valid {
+            test += rng.range(1, 100);
+            if is_solvable(test, &numbers) {
+                continue;
+            }
+        } else {
+            result += test;
+        }
+
+        let numbers = numbers
+            .iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        lines.push(format!("{test}: {numbers}"));
+    }
+
+    (lines.join("\n"), result)
+}
+
+fn is_solvable(test: usize, numbers: &[usize]) -> bool {
+    (0..1u64 << (numbers.len() - 1)).any(|operators| {
+        let value = numbers[1..]
+            .iter()
+            .enumerate()
+            .try_fold(numbers[0], |acc, (i, n)| match operators >> i & 1 {
+                1 => acc.checked_mul(*n),
+                _ => acc.checked_add(*n),
+            });
+        value == Some(test)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rng() {
+        let mut rng = Rng::new(7);
+        let values = (0..100).map(|_| rng.range(3, 9)).collect::<Vec<_>>();
+        assert!(values.iter().all(|v| (3..9).contains(v)));
+        assert_eq!(values, {
+            let mut rng = Rng::new(7);
+            (0..100).map(|_| rng.range(3, 9)).collect::<Vec<_>>()
+        });
+    }
+
+    #[test]
+    fn test_word_search() {
+        let mut rng = Rng::new(1);
+        let (text, count) = word_search(&mut rng, 20, 10, "XMAS", 15);
+
+        assert_eq!(10, text.lines().count());
+        assert!(text.lines().all(|l| l.len() == 20));
+        assert!(count > 0);
+
+        let grid = text
+            .lines()
+            .map(|l| l.chars().collect())
+            .collect::<Vec<_>>();
+        assert_eq!(count, count_word(&grid, &['X', 'M', 'A', 'S']));
+    }
+
+    #[test]
+    fn test_lab() {
+        let mut rng = Rng::new(2);
+        for size in [4, 5, 10, 30] {
+            let (text, visited) = lab(&mut rng, size, GuardFate::Exits);
+            assert_eq!(1, text.matches('^').count());
+            assert!(visited.is_some_and(|v| v >= 1));
+
+            let (text, visited) = lab(&mut rng, size, GuardFate::Loops);
+            assert_eq!(size, text.lines().count());
+            assert_eq!(None, visited);
+        }
+    }
+
+    #[test]
+    fn test_calibrations() {
+        let mut rng = Rng::new(3);
+        let (text, _) = calibrations(&mut rng, 50, 0.5);
+        assert_eq!(50, text.lines().count());
+
+        assert!(is_solvable(3267, &[81, 40, 27]));
+        assert!(!is_solvable(6, &[2, 3, 4]));
+    }
+}
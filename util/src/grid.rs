@@ -0,0 +1,470 @@
+use std::collections::HashMap;
+
+use crate::stats::BoundingBox;
+use crate::{Direction, Neighbor};
+
+/// Signed so unbounded grids can extend past the top and left edges.
+pub type Position = (i64, i64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topology {
+    Bounded,
+    /// Stepping past an edge re-enters from the opposite edge.
+    Wrapping,
+    Unbounded,
+}
+
+pub trait Grid {
+    type Cell;
+
+    fn cell(&self, position: Position) -> Option<&Self::Cell>;
+
+    /// Top left and bottom right positions, inclusive.
+    fn bounds(&self) -> Option<(Position, Position)>;
+
+    fn contains(&self, position: Position) -> bool;
+
+    fn neighbor(
+        &self,
+        topology: Topology,
+        direction: Direction,
+        (x, y): Position,
+    ) -> Option<Neighbor<Position>> {
+        let (dx, dy) = direction.offset();
+        let position = (x.checked_add(dx)?, y.checked_add(dy)?);
+
+        let position = match topology {
+            Topology::Bounded => position,
+            Topology::Wrapping => {
+                let ((min_x, min_y), (max_x, max_y)) = self.bounds()?;
+                (
+                    wrap(position.0, min_x, max_x),
+                    wrap(position.1, min_y, max_y),
+                )
+            }
+            Topology::Unbounded => return Some(Neighbor::at(direction, position)),
+        };
+
+        self.contains(position)
+            .then(|| Neighbor::at(direction, position))
+    }
+
+    fn neighbors(
+        &self,
+        topology: Topology,
+        position: Position,
+        include_diagonals: bool,
+    ) -> Vec<Neighbor<Position>> {
+        let directions: &[Direction] = if include_diagonals {
+            &Direction::ALL
+        } else {
+            &Direction::ORTHOGONAL
+        };
+
+        directions
+            .iter()
+            .filter_map(|&d| self.neighbor(topology, d, position))
+            .collect()
+    }
+}
+
+fn wrap(value: i64, min: i64, max: i64) -> i64 {
+    min + (value - min).rem_euclid(max - min + 1)
+}
+
+impl<T> Grid for [Vec<T>] {
+    type Cell = T;
+
+    fn cell(&self, (x, y): Position) -> Option<&T> {
+        let x = usize::try_from(x).ok()?;
+        let y = usize::try_from(y).ok()?;
+        self.get(y)?.get(x)
+    }
+
+    fn bounds(&self) -> Option<(Position, Position)> {
+        let width = self.iter().map(Vec::len).max().filter(|&w| w > 0)?;
+        Some(((0, 0), (width as i64 - 1, self.len() as i64 - 1)))
+    }
+
+    fn contains(&self, position: Position) -> bool {
+        self.cell(position).is_some()
+    }
+}
+
+impl<T> Grid for Vec<Vec<T>> {
+    type Cell = T;
+
+    fn cell(&self, position: Position) -> Option<&T> {
+        self.as_slice().cell(position)
+    }
+
+    fn bounds(&self) -> Option<(Position, Position)> {
+        self.as_slice().bounds()
+    }
+
+    fn contains(&self, position: Position) -> bool {
+        Grid::contains(self.as_slice(), position)
+    }
+}
+
+/// Removing a cell doesn't shrink the bounds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Position, T>,
+    bounds: BoundingBox<i64>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+            bounds: BoundingBox::new(),
+        }
+    }
+
+    pub fn get(&self, position: Position) -> Option<&T> {
+        self.cells.get(&position)
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        self.cells.get_mut(&position)
+    }
+
+    pub fn insert(&mut self, position: Position, value: T) -> Option<T> {
+        self.bounds.push(position);
+        self.cells.insert(position, value)
+    }
+
+    pub fn remove(&mut self, position: Position) -> Option<T> {
+        self.cells.remove(&position)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.cells.iter().map(|(&p, v)| (p, v))
+    }
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<(Position, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Position, T)>>(iter: I) -> Self {
+        let mut grid = Self::new();
+        for (position, value) in iter {
+            grid.insert(position, value);
+        }
+        grid
+    }
+}
+
+impl<T> From<Vec<Vec<T>>> for SparseGrid<T> {
+    fn from(value: Vec<Vec<T>>) -> Self {
+        value
+            .into_iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.into_iter()
+                    .enumerate()
+                    .map(move |(x, cell)| ((x as i64, y as i64), cell))
+            })
+            .collect()
+    }
+}
+
+impl<T> Grid for SparseGrid<T> {
+    type Cell = T;
+
+    fn cell(&self, position: Position) -> Option<&T> {
+        self.get(position)
+    }
+
+    fn bounds(&self) -> Option<(Position, Position)> {
+        self.bounds.min().zip(self.bounds.max())
+    }
+
+    fn contains(&self, position: Position) -> bool {
+        self.bounds.contains(&position)
+    }
+}
+
+/// Positions in `direction` from `start`, inclusive, until the edge of the grid.
+pub fn ray<G: Grid + ?Sized>(grid: &G, start: Position, direction: Direction) -> Ray<'_, G> {
+    Ray {
+        grid,
+        direction,
+        next: grid.contains(start).then_some(start),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Ray<'a, G: ?Sized> {
+    grid: &'a G,
+    direction: Direction,
+    next: Option<Position>,
+}
+
+impl<'a, G: Grid + ?Sized> Ray<'a, G> {
+    /// Skips positions without a cell.
+    pub fn cells(self) -> impl Iterator<Item = (Position, &'a G::Cell)> {
+        let grid = self.grid;
+        self.filter_map(move |p| grid.cell(p).map(|c| (p, c)))
+    }
+}
+
+impl<G: Grid + ?Sized> Iterator for Ray<'_, G> {
+    type Item = Position;
+
+    fn next(&mut self) -> Option<Position> {
+        let position = self.next?;
+        self.next = self
+            .grid
+            .neighbor(Topology::Bounded, self.direction, position)
+            .map(Position::from);
+        Some(position)
+    }
+}
+
+/// Positions from `a` to `b`, inclusive, by Bresenham's algorithm.
+pub fn line_between(a: Position, b: Position) -> Segment {
+    let dx = (b.0 - a.0).abs();
+    let dy = -(b.1 - a.1).abs();
+
+    Segment {
+        next: Some(a),
+        end: b,
+        step: ((b.0 - a.0).signum(), (b.1 - a.1).signum()),
+        delta: (dx, dy),
+        error: dx + dy,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Segment {
+    next: Option<Position>,
+    end: Position,
+    step: Position,
+    delta: (i64, i64),
+    error: i64,
+}
+
+impl Iterator for Segment {
+    type Item = Position;
+
+    fn next(&mut self) -> Option<Position> {
+        let (x, y) = self.next?;
+
+        self.next = (self.end != (x, y)).then(|| {
+            let (dx, dy) = self.delta;
+            let mut next = (x, y);
+
+            let double = 2 * self.error;
+            if double >= dy {
+                self.error += dy;
+                next.0 += self.step.0;
+            }
+            if double <= dx {
+                self.error += dx;
+                next.1 += self.step.1;
+            }
+
+            next
+        });
+
+        Some((x, y))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn positions(mut neighbors: Vec<Neighbor<Position>>) -> Vec<Position> {
+        neighbors.sort_unstable();
+        neighbors.into_iter().map(|n| n.position).collect()
+    }
+
+    #[test]
+    fn test_dense_topologies() {
+        let grid = vec![vec![0; 4]; 3];
+
+        assert_eq!(
+            vec![(0, 1), (1, 0)],
+            positions(grid.neighbors(Topology::Bounded, (0, 0), false))
+        );
+        assert_eq!(
+            vec![(0, 2), (0, 1), (3, 0), (1, 0)],
+            positions(grid.neighbors(Topology::Wrapping, (0, 0), false))
+        );
+        assert_eq!(
+            vec![(0, -1), (0, 1), (-1, 0), (1, 0)],
+            positions(grid.neighbors(Topology::Unbounded, (0, 0), false))
+        );
+
+        assert_eq!(
+            Some((0, 0)),
+            grid.neighbor(Topology::Wrapping, Direction::LowerRight, (3, 2))
+                .map(|n| n.position)
+        );
+        assert_eq!(
+            None,
+            grid.neighbor(Topology::Bounded, Direction::LowerRight, (3, 2))
+        );
+        assert_eq!(8, grid.neighbors(Topology::Wrapping, (3, 2), true).len());
+
+        assert_eq!(
+            None,
+            Vec::<Vec<u8>>::new().neighbor(Topology::Wrapping, Direction::Up, (0, 0))
+        );
+    }
+
+    #[test]
+    fn test_next() {
+        let grid = vec![vec![0; 3]; 3];
+
+        let mut neighbor: Neighbor<Position> = Neighbor::at(Direction::Right, (1, 1));
+        let mut visited = Vec::new();
+        for _ in 0..4 {
+            neighbor = neighbor.next(&grid, Topology::Wrapping).unwrap();
+            visited.push(neighbor.position);
+        }
+        assert_eq!(vec![(2, 1), (0, 1), (1, 1), (2, 1)], visited);
+
+        let neighbor: Neighbor<Position> = Neighbor::at(Direction::Up, (0, 0));
+        assert_eq!(None, neighbor.next(&grid, Topology::Bounded));
+        assert_eq!(
+            Some((0, -1)),
+            neighbor
+                .next(&grid, Topology::Unbounded)
+                .map(Position::from)
+        );
+    }
+
+    #[test]
+    fn test_sparse() {
+        let mut grid: SparseGrid<char> = [((-2, -1), '#'), ((1, 2), '#')].into_iter().collect();
+        assert_eq!(2, grid.len());
+        assert_eq!(Some(((-2, -1), (1, 2))), grid.bounds());
+
+        // Positions without cells are still within the bounds
+        assert_eq!(
+            vec![(0, -1), (0, 1), (-1, 0), (1, 0)],
+            positions(grid.neighbors(Topology::Bounded, (0, 0), false))
+        );
+        assert_eq!(
+            Some((-2, -1)),
+            grid.neighbor(Topology::Wrapping, Direction::LowerRight, (1, 2))
+                .map(|n| n.position)
+        );
+        assert_eq!(
+            Some('#'),
+            grid.neighbor(Topology::Wrapping, Direction::LowerRight, (1, 2))
+                .and_then(|n| grid.cell(n.position))
+                .copied()
+        );
+        assert_eq!(
+            None,
+            grid.neighbor(Topology::Bounded, Direction::Left, (-2, 0))
+        );
+        assert_eq!(
+            4,
+            grid.neighbors(Topology::Unbounded, (100, 100), false).len()
+        );
+
+        assert_eq!(Some('#'), grid.insert((1, 2), '.'));
+        assert_eq!(Some('.'), grid.remove((1, 2)));
+        assert_eq!(None, grid.get((1, 2)));
+        assert_eq!(Some(((-2, -1), (1, 2))), grid.bounds());
+    }
+
+    #[test]
+    fn test_sparse_from_dense() {
+        let grid = SparseGrid::from(vec![vec!['a', 'b'], vec!['c']]);
+        assert_eq!(3, grid.len());
+        assert_eq!(Some(&'c'), grid.get((0, 1)));
+        assert_eq!(None, grid.get((1, 1)));
+        assert_eq!(Some(((0, 0), (1, 1))), grid.bounds());
+    }
+
+    #[test]
+    fn test_ray() {
+        let grid = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+
+        assert_eq!(
+            vec![(0, 1), (1, 1), (2, 1)],
+            ray(&grid, (0, 1), Direction::Right).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![5, 1],
+            ray(&grid, (1, 1), Direction::UpperLeft)
+                .cells()
+                .map(|(_, &c)| c)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(2, 2), (2, 1)],
+            ray(&grid, (2, 2), Direction::Up)
+                .cells()
+                .take_while(|(_, &c)| c > 5)
+                .map(|(p, _)| p)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(0, ray(&grid, (3, 0), Direction::Left).count());
+
+        // Positions between sparse cells are passed through but have no cell
+        let grid: SparseGrid<char> = [((0, 0), 'a'), ((3, 0), 'b')].into_iter().collect();
+        assert_eq!(4, ray(&grid, (0, 0), Direction::Right).count());
+        assert_eq!(
+            vec![((0, 0), &'a'), ((3, 0), &'b')],
+            ray(&grid, (0, 0), Direction::Right)
+                .cells()
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_line_between() {
+        assert_eq!(
+            vec![(0, 0), (1, 0), (2, 0)],
+            line_between((0, 0), (2, 0)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(2, 2), (1, 1), (0, 0), (-1, -1)],
+            line_between((2, 2), (-1, -1)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(0, 0), (1, 1), (2, 1), (3, 2), (4, 2)],
+            line_between((0, 0), (4, 2)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(5, 5)],
+            line_between((5, 5), (5, 5)).collect::<Vec<_>>()
+        );
+
+        for (a, b) in [((0, 0), (7, -3)), ((-4, 9), (2, -11)), ((3, 1), (-8, 1))] {
+            let line = line_between(a, b).collect::<Vec<_>>();
+            assert_eq!(Some(&a), line.first());
+            assert_eq!(Some(&b), line.last());
+            let length = (b.0 - a.0).abs().max((b.1 - a.1).abs());
+            assert_eq!(length as usize + 1, line.len());
+            for pair in line.windows(2) {
+                assert_eq!(
+                    1,
+                    (pair[1].0 - pair[0].0)
+                        .abs()
+                        .max((pair[1].1 - pair[0].1).abs())
+                );
+            }
+        }
+    }
+}
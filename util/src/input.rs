@@ -0,0 +1,205 @@
+use std::fmt::{Display, Formatter};
+
+use anyhow::Result;
+
+/// Puzzle input text, which keeps track of where each line came from so parse errors can say
+/// where they occurred.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Input(String);
+
+impl Input {
+    /// The input exactly as it was read.
+    pub fn raw(&self) -> &str {
+        &self.0
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = Line<'_>> {
+        self.0.lines().enumerate().map(|(i, text)| Line {
+            number: i + 1,
+            text,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.lines().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Sections of consecutive non-blank lines, separated by one or more blank lines.
+    pub fn paragraphs(&self) -> Vec<Vec<Line<'_>>> {
+        let mut paragraphs = Vec::new();
+        let mut paragraph = Vec::new();
+
+        for line in self.lines() {
+            if line.text.trim().is_empty() {
+                if !paragraph.is_empty() {
+                    paragraphs.push(paragraph);
+                    paragraph = Vec::new();
+                }
+            } else {
+                paragraph.push(line);
+            }
+        }
+
+        if !paragraph.is_empty() {
+            paragraphs.push(paragraph);
+        }
+
+        paragraphs
+    }
+
+    /// Each line's characters, indexed as `grid[y][x]`.
+    pub fn grid(&self) -> Vec<Vec<char>> {
+        self.lines().map(|l| l.text.chars().collect()).collect()
+    }
+}
+
+impl From<String> for Input {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for Input {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+/// A line of [`Input`], along with its 1-based line number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// Parses the line with `f`, attaching the line number to any error.
+    pub fn parse<T>(&self, f: impl FnOnce(&'a str) -> Result<T>) -> Result<T> {
+        f(self.text).at_line(self.number)
+    }
+
+    /// The 1-based column at which `part` starts, or `None` if it isn't a slice of this line's
+    /// text.
+    pub fn column_of(&self, part: &str) -> Option<usize> {
+        column_in(self.text, part)
+    }
+}
+
+/// The 1-based column at which `part` starts, or `None` if it isn't a slice of `text`.
+pub(crate) fn column_in(text: &str, part: &str) -> Option<usize> {
+    let offset = (part.as_ptr() as usize).checked_sub(text.as_ptr() as usize)?;
+    (offset.checked_add(part.len())? <= text.len()).then_some(offset + 1)
+}
+
+/// Where in the [`Input`] an error occurred, attached to errors as context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: Option<usize>,
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.column {
+            Some(column) => write!(f, "line {}, column {}", self.line, column),
+            None => write!(f, "line {}", self.line),
+        }
+    }
+}
+
+/// Attaches a [`Location`] to errors, e.g. `usize::from_str(s).at_line(line.number)`.
+pub trait LocationContext<T> {
+    fn at_line(self, line: usize) -> Result<T>;
+
+    fn at(self, line: usize, column: usize) -> Result<T>;
+}
+
+impl<T, E: Into<anyhow::Error>> LocationContext<T> for Result<T, E> {
+    fn at_line(self, line: usize) -> Result<T> {
+        self.map_err(|e| e.into().context(Location { line, column: None }))
+    }
+
+    fn at(self, line: usize, column: usize) -> Result<T> {
+        self.map_err(|e| {
+            e.into().context(Location {
+                line,
+                column: Some(column),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use anyhow::anyhow;
+
+    use super::*;
+
+    #[test]
+    fn test_lines_and_paragraphs() {
+        let input = Input::from("ab\ncd\n\n\nef\n");
+
+        assert_eq!(5, input.len());
+        assert_eq!(
+            vec![(1, "ab"), (2, "cd"), (3, ""), (4, ""), (5, "ef")],
+            input
+                .lines()
+                .map(|l| (l.number, l.text))
+                .collect::<Vec<_>>()
+        );
+
+        let paragraphs = input.paragraphs();
+        assert_eq!(2, paragraphs.len());
+        assert_eq!(
+            vec![1, 2],
+            paragraphs[0].iter().map(|l| l.number).collect::<Vec<_>>()
+        );
+        assert_eq!(5, paragraphs[1][0].number);
+
+        assert_eq!(
+            vec![
+                vec!['a', 'b'],
+                vec!['c', 'd'],
+                vec![],
+                vec![],
+                vec!['e', 'f']
+            ],
+            input.grid()
+        );
+        assert_eq!("ab\ncd\n\n\nef\n", input.raw());
+    }
+
+    #[test]
+    fn test_location() {
+        let input = Input::from("1 2\n3 x");
+        let line = input.lines().nth(1).unwrap();
+
+        let err = line.parse(|s| Ok(usize::from_str(s)?)).unwrap_err();
+        assert_eq!(
+            Some(&Location {
+                line: 2,
+                column: None
+            }),
+            err.downcast_ref::<Location>()
+        );
+        assert_eq!("line 2: invalid digit found in string", format!("{err:#}"));
+
+        let part = line.text.split_ascii_whitespace().nth(1).unwrap();
+        assert_eq!(Some(3), line.column_of(part));
+        assert_eq!(Some(4), line.column_of(&line.text[3..]));
+        assert_eq!(None, line.column_of("x"));
+        assert_eq!(None, line.column_of(input.lines().next().unwrap().text));
+
+        let err = Err::<(), _>(anyhow!("Bad token")).at(line.number, 3);
+        assert_eq!(
+            "line 2, column 3: Bad token",
+            format!("{:#}", err.unwrap_err())
+        );
+    }
+}
@@ -1,123 +1,138 @@
+use std::fs;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufReader;
+use std::num::TryFromIntError;
 use std::path::Path;
-use std::str::FromStr;
 
-use anyhow::{anyhow, Result};
-use clap::{Arg, ArgAction, Command};
+use anyhow::{anyhow, Context, Result};
 use env_logger::Target;
 use log::{LevelFilter, trace};
 
+use crate::grid::{Grid, Position, Topology};
+
+pub use cli::{Cli, Config, Format, Generator, InputType, Parsed, Part, Parts};
+pub use input::{Input, Line, Location, LocationContext};
+pub use report::Reporter;
+pub use solution::{example_input, Solution};
+pub use stats::MinMax;
+
+mod cli;
+pub mod cycle;
+pub mod differential;
+pub mod generate;
+pub mod grid;
+mod input;
+pub mod linalg;
+pub mod math;
+pub mod memo;
+pub mod parse;
+pub mod range_set;
+pub mod region;
+mod report;
+pub mod search;
+mod solution;
+pub mod stats;
+
 const INPUT_PATH: &str = "input/input";
 const TEST_INPUT_PATH: &str = "input/example";
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Input {
-    Test,
-    Actual,
+pub fn init_test() -> Result<Input> {
+    init_test_logger()?;
+    test_input()
 }
 
-impl FromStr for Input {
-    type Err = anyhow::Error;
+fn init_logger(level: LevelFilter, target: Target) -> Result<()> {
+    inner_init_logger(Some(level), target, false)
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "test" => Ok(Self::Test),
-            "actual" => Ok(Self::Actual),
-            _ => Err(anyhow!("Invalid input type: {}", s)),
-        }
-    }
+pub fn init_test_logger() -> Result<()> {
+    inner_init_logger(Some(LevelFilter::Trace), Target::Stdout, true)
 }
 
-pub fn init() -> Result<Vec<String>> {
-    let matches = Command::new("advent-of-code")
-        .arg(
-            Arg::new("verbose")
-                .short('v')
-                .long("verbose")
-                .action(ArgAction::SetTrue)
-                .help("increase log level from the default for the input type"),
-        )
-        .arg(
-            Arg::new("input")
-                .short('i')
-                .long("input")
-                .default_value("actual")
-                .help(format!(
-                    "input type, {:?} or {:?}",
-                    Input::Test,
-                    Input::Actual
-                )),
-        )
-        .get_matches();
-
-    let verbose = matches.get_flag("verbose");
-    let input = matches
-        .get_one::<String>("input")
-        .map(|s| Input::from_str(s))
-        .unwrap()?;
-
-    let log_level = match (input, verbose) {
-        (Input::Actual, false) => LevelFilter::Info,
-        (Input::Actual, true) => LevelFilter::Debug,
-        (Input::Test, false) => LevelFilter::Debug,
-        (Input::Test, true) => LevelFilter::Trace,
-    };
+fn inner_init_logger(level: Option<LevelFilter>, target: Target, is_test: bool) -> Result<()> {
+    let _ = env_logger::builder()
+        .target(target)
+        .filter_level(level.unwrap_or(LevelFilter::Info))
+        .is_test(is_test)
+        .try_init();
 
-    init_logger(log_level)?;
+    Ok(())
+}
 
-    match input {
-        Input::Actual => self::input(),
-        Input::Test => test_input(),
-    }
+pub fn input() -> Result<Input> {
+    input_with(ReadOptions::default())
 }
 
-pub fn init_test() -> Result<Vec<String>> {
-    init_test_logger()?;
-    test_input()
+pub fn test_input() -> Result<Input> {
+    test_input_with(ReadOptions::default())
 }
 
-fn init_logger(level: LevelFilter) -> Result<()> {
-    inner_init_logger(Some(level), false)
+pub fn input_with(options: ReadOptions) -> Result<Input> {
+    read_input(INPUT_PATH, options)
 }
 
-pub fn init_test_logger() -> Result<()> {
-    inner_init_logger(Some(LevelFilter::Trace), true)
+pub fn test_input_with(options: ReadOptions) -> Result<Input> {
+    read_input(TEST_INPUT_PATH, options)
 }
 
-fn inner_init_logger(level: Option<LevelFilter>, is_test: bool) -> Result<()> {
-    let _ = env_logger::builder()
-        .target(Target::Stdout)
-        .filter_level(level.unwrap_or(LevelFilter::Info))
-        .is_test(is_test)
-        .try_init();
+pub fn input_reader() -> Result<BufReader<File>> {
+    open(INPUT_PATH)
+}
 
-    Ok(())
+pub fn test_input_reader() -> Result<BufReader<File>> {
+    open(TEST_INPUT_PATH)
 }
 
-pub fn input() -> Result<Vec<String>> {
-    read_lines(INPUT_PATH)
+fn open(path: impl AsRef<Path>) -> Result<BufReader<File>> {
+    let path = path.as_ref();
+    File::open(path)
+        .map(BufReader::new)
+        .with_context(|| format!("Failed to open {}", path.display()))
 }
 
-pub fn test_input() -> Result<Vec<String>> {
-    read_lines(TEST_INPUT_PATH)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReadOptions {
+    /// Replace invalid UTF-8 with U+FFFD rather than failing.
+    pub lossy: bool,
+    /// Accept an input with no content, for days where that's meaningful.
+    pub allow_empty: bool,
 }
 
-fn read_lines(path: &'static str) -> Result<Vec<String>> {
-    let lines: Vec<_> = BufReader::new(File::open(Path::new(path))?)
-        .lines()
-        .map_while(Result::ok)
-        .inspect(|l| trace!("{}", l))
-        .collect();
-
-    if !lines.is_empty() {
-        Ok(lines)
-    } else {
-        Err(anyhow!("No input: {}", path))
+/// Reads the whole input at `path`, normalizing CRLF line endings to LF and removing trailing
+/// newlines. Fails, naming the path, rather than returning partial input.
+pub fn read_input(path: impl AsRef<Path>, options: ReadOptions) -> Result<Input> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let text = match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(e) if options.lossy => String::from_utf8_lossy(e.as_bytes()).into_owned(),
+        Err(e) => {
+            let valid = &e.as_bytes()[..e.utf8_error().valid_up_to()];
+            let line = valid.iter().filter(|b| **b == b'\n').count() + 1;
+            let column = valid.iter().rev().take_while(|b| **b != b'\n').count() + 1;
+
+            return Err(anyhow!("Invalid UTF-8: {}", e.utf8_error()))
+                .at(line, column)
+                .with_context(|| format!("Failed to read {}", path.display()));
+        }
+    };
+
+    let text = text.replace("\r\n", "\n");
+    let text = text.trim_end_matches('\n');
+
+    for line in text.lines() {
+        trace!("{}", line);
     }
+
+    if text.is_empty() && !options.allow_empty {
+        return Err(anyhow!("No input: {}", path.display()));
+    }
+
+    Ok(Input::from(text))
 }
 
-#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub enum Direction {
     Up,
     Down,
@@ -144,84 +159,72 @@ impl From<Direction> for char {
     }
 }
 
+impl Direction {
+    pub const ORTHOGONAL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+        Direction::UpperLeft,
+        Direction::UpperRight,
+        Direction::LowerLeft,
+        Direction::LowerRight,
+    ];
+
+    /// Change in (x, y) for one step, with y increasing downwards.
+    pub fn offset(self) -> (i64, i64) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+            Direction::UpperLeft => (-1, -1),
+            Direction::UpperRight => (1, -1),
+            Direction::LowerLeft => (-1, 1),
+            Direction::LowerRight => (1, 1),
+        }
+    }
+}
+
+/// A step from a position, by default on a dense grid indexed by `usize`, or by [`Position`] on
+/// any [`Grid`].
 #[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq)]
-pub struct Neighbor {
+pub struct Neighbor<P = (usize, usize)> {
     pub direction: Direction,
-    pub position: (usize, usize),
+    pub position: P,
 }
 
-impl Neighbor {
-    pub fn new(direction: Direction, x: usize, y: usize) -> Self {
+impl<P> Neighbor<P> {
+    pub fn at(direction: Direction, position: P) -> Self {
         Self {
             direction,
-            position: (x, y),
+            position,
         }
     }
+}
+
+impl Neighbor {
+    pub fn new(direction: Direction, x: usize, y: usize) -> Self {
+        Self::at(direction, (x, y))
+    }
 
     pub fn next<T>(self, grid: &[Vec<T>]) -> Option<Neighbor> {
-        let Neighbor {
-            direction,
-            position: (x, y),
-        } = self;
-
-        match direction {
-            Direction::Right => {
-                if grid.get(y).and_then(|r| r.get(x + 1)).is_some() {
-                    Some(Self::new(Direction::Right, x + 1, y))
-                } else {
-                    None
-                }
-            }
-            Direction::Left => {
-                if grid.get(y).is_some() && x > 0 {
-                    Some(Self::new(Direction::Left, x - 1, y))
-                } else {
-                    None
-                }
-            }
-            Direction::Up => {
-                if y > 0 {
-                    Some(Self::new(Direction::Up, x, y - 1))
-                } else {
-                    None
-                }
-            }
-            Direction::Down => {
-                if grid.get(y + 1).and_then(|r| r.get(x)).is_some() {
-                    Some(Self::new(Direction::Down, x, y + 1))
-                } else {
-                    None
-                }
-            }
-            Direction::UpperRight => {
-                if y > 0 && grid[y - 1].get(x + 1).is_some() {
-                    Some(Self::new(Direction::UpperRight, x + 1, y - 1))
-                } else {
-                    None
-                }
-            }
-            Direction::UpperLeft => {
-                if y > 0 && x > 0 {
-                    Some(Self::new(Direction::UpperLeft, x - 1, y - 1))
-                } else {
-                    None
-                }
-            }
-            Direction::LowerRight => {
-                if grid.get(y + 1).and_then(|r| r.get(x + 1)).is_some() {
-                    Some(Self::new(Direction::LowerRight, x + 1, y + 1))
-                } else {
-                    None
-                }
-            }
-            Direction::LowerLeft => {
-                if grid.get(y + 1).is_some() && x > 0 {
-                    Some(Self::new(Direction::LowerLeft, x - 1, y + 1))
-                } else {
-                    None
-                }
-            }
-        }
+        let (x, y) = self.position;
+        neighbor_in_direction(grid, self.direction, x, y)
+    }
+}
+
+impl Neighbor<Position> {
+    /// The next neighbor in the same direction, see [`Grid::neighbor`].
+    pub fn next<G: Grid + ?Sized>(self, grid: &G, topology: Topology) -> Option<Self> {
+        grid.neighbor(topology, self.direction, self.position)
     }
 }
 
@@ -231,99 +234,53 @@ impl From<Neighbor> for (usize, usize) {
     }
 }
 
+impl From<Neighbor<Position>> for Position {
+    fn from(value: Neighbor<Position>) -> Self {
+        value.position
+    }
+}
+
+impl TryFrom<Neighbor<Position>> for Neighbor {
+    type Error = TryFromIntError;
+
+    /// Fails for negative positions, which can come from any topology but [`Topology::Bounded`].
+    fn try_from(value: Neighbor<Position>) -> Result<Self, Self::Error> {
+        let (x, y) = value.position;
+        Ok(Neighbor::new(
+            value.direction,
+            usize::try_from(x)?,
+            usize::try_from(y)?,
+        ))
+    }
+}
+
+/// The neighbor of (x, y) on a bounded grid, see [`Grid::neighbor`] for other topologies.
 pub fn neighbor_in_direction<T>(
     grid: &[Vec<T>],
     direction: Direction,
     x: usize,
     y: usize,
 ) -> Option<Neighbor> {
-    match direction {
-        Direction::Up => y.checked_sub(1).map(|y| Neighbor::new(direction, x, y)),
-        Direction::Down => grid.get(y + 1).map(|_| Neighbor::new(direction, x, y + 1)),
-        Direction::Left => x.checked_sub(1).map(|x| Neighbor::new(direction, x, y)),
-        Direction::Right => grid[y]
-            .get(x + 1)
-            .map(|_| Neighbor::new(direction, x + 1, y)),
-        Direction::UpperLeft => y
-            .checked_sub(1)
-            .filter(|_| x > 0)
-            .map(|y| Neighbor::new(direction, x - 1, y)),
-        Direction::UpperRight => y
-            .checked_sub(1)
-            .and_then(|y| grid[y].get(x + 1))
-            .map(|_| Neighbor::new(direction, x + 1, y - 1)),
-        Direction::LowerLeft => grid
-            .get(y + 1)
-            .filter(|_| x > 0)
-            .map(|_| Neighbor::new(direction, x - 1, y + 1)),
-        Direction::LowerRight => grid
-            .get(y + 1)
-            .and_then(|_| grid[y + 1].get(x + 1))
-            .map(|_| Neighbor::new(direction, x + 1, y + 1)),
-    }
+    grid.neighbor(Topology::Bounded, direction, (x as i64, y as i64))
+        .and_then(|n| Neighbor::try_from(n).ok())
 }
 
+/// The neighbors of (x, y) on a bounded grid, see [`Grid::neighbors`] for other topologies.
 pub fn neighbors<T>(grid: &[Vec<T>], x: usize, y: usize, include_diagonals: bool) -> Vec<Neighbor> {
-    let mut directions = vec![
-        Direction::Up,
-        Direction::Down,
-        Direction::Left,
-        Direction::Right,
-    ];
-
-    if include_diagonals {
-        directions.extend_from_slice(&[
-            Direction::UpperLeft,
-            Direction::UpperRight,
-            Direction::LowerLeft,
-            Direction::LowerRight,
-        ]);
-    }
-
-    directions
+    grid.neighbors(Topology::Bounded, (x as i64, y as i64), include_diagonals)
         .into_iter()
-        .filter_map(|d| neighbor_in_direction(grid, d, x, y))
+        .filter_map(|n| Neighbor::try_from(n).ok())
         .collect()
 }
 
-#[derive(Debug)]
-pub struct MinMax {
-    pub min: Option<usize>,
-    pub max: Option<usize>,
-}
-
-impl FromIterator<usize> for MinMax {
-    fn from_iter<T: IntoIterator<Item = usize>>(iter: T) -> Self {
-        let mut min = None;
-        let mut max = None;
-
-        for i in iter {
-            min = match min {
-                None => Some(i),
-                Some(m) => Some(usize::min(m, i)),
-            };
-            max = match max {
-                None => Some(i),
-                Some(m) => Some(usize::max(m, i)),
-            };
-        }
-
-        MinMax { min, max }
-    }
-}
-
+#[deprecated(note = "use math::gcd")]
 pub fn greatest_common_divisor(a: usize, b: usize) -> usize {
-    if b > a {
-        greatest_common_divisor(b, a)
-    } else if b == 0 {
-        a
-    } else {
-        greatest_common_divisor(b, a % b)
-    }
+    math::gcd(a, b)
 }
 
+#[deprecated(note = "use math::lcm, which returns None on overflow")]
 pub fn least_common_multiple(a: usize, b: usize) -> usize {
-    (a * b) / greatest_common_divisor(a, b)
+    math::lcm(a, b).expect("Least common multiple overflowed")
 }
 
 #[cfg(test)]
@@ -474,7 +431,74 @@ mod test {
     }
 
     #[test]
+    fn test_neighbor_conversion() {
+        let convert =
+            |direction, position: Position| Neighbor::try_from(Neighbor::at(direction, position));
+        assert_eq!(
+            Ok(Neighbor::new(Direction::Up, 3, 0)),
+            convert(Direction::Up, (3, 0))
+        );
+        assert!(convert(Direction::Up, (3, -1)).is_err());
+        assert!(convert(Direction::Left, (-1, 0)).is_err());
+
+        // Wrapping keeps positions on the grid, so every neighbor converts
+        let grid = vec![vec![0; 4]; 2];
+        let wrapped: Vec<Neighbor> = grid
+            .neighbors(Topology::Wrapping, (0, 0), false)
+            .into_iter()
+            .map(|n| Neighbor::try_from(n).unwrap())
+            .collect();
+        assert!(wrapped.contains(&Neighbor::new(Direction::Left, 3, 0)));
+        assert!(wrapped.contains(&Neighbor::new(Direction::Up, 0, 1)));
+    }
+
+    fn write_input(dir: &tempfile::TempDir, name: &str, contents: &[u8]) -> std::path::PathBuf {
+        let path = dir.path().join(name);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_read_input() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let path = write_input(&dir, "crlf", b"1 2\r\n3 4\r\n\r\n");
+        assert_eq!("1 2\n3 4", read_input(&path, ReadOptions::default())?.raw());
+
+        let path = write_input(&dir, "utf8", b"ok\nab\xffcd\nmore");
+        let err = read_input(&path, ReadOptions::default()).unwrap_err();
+        assert_eq!(
+            Some(&Location {
+                line: 2,
+                column: Some(3)
+            }),
+            err.downcast_ref::<Location>()
+        );
+        assert!(format!("{err:#}").contains(&path.display().to_string()));
+
+        let lossy = ReadOptions {
+            lossy: true,
+            ..ReadOptions::default()
+        };
+        assert_eq!("ok\nab\u{fffd}cd\nmore", read_input(&path, lossy)?.raw());
+
+        let path = write_input(&dir, "empty", b"\n");
+        assert!(read_input(&path, ReadOptions::default()).is_err());
+
+        let allow_empty = ReadOptions {
+            allow_empty: true,
+            ..ReadOptions::default()
+        };
+        assert!(read_input(&path, allow_empty)?.is_empty());
+
+        assert!(read_input(path.with_extension("missing"), ReadOptions::default()).is_err());
+
+        Ok(())
+    }
+    #[test]
+    #[allow(deprecated)]
     fn test_greatest_common_divisor() {
         assert_eq!(6, greatest_common_divisor(48, 18));
+        assert_eq!(144, least_common_multiple(48, 18));
     }
 }
@@ -0,0 +1,528 @@
+use std::fmt::Display;
+
+use crate::math::{add_mod, checked_gcd, mul_mod, Integer};
+
+pub type Matrix<T, const N: usize> = [[T; N]; N];
+
+/// In lowest terms, with a positive denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    pub const ZERO: Self = Self::integer(0);
+    pub const ONE: Self = Self::integer(1);
+
+    /// `None` if the denominator is 0 or normalising overflows, e.g. `1 / i128::MIN`.
+    pub fn new(numerator: i128, denominator: i128) -> Option<Self> {
+        if denominator == 0 {
+            return None;
+        }
+
+        let divisor = checked_gcd(numerator, denominator)?;
+        let (numerator, denominator) = (numerator / divisor, denominator / divisor);
+        if denominator < 0 {
+            Some(Self {
+                numerator: numerator.checked_neg()?,
+                denominator: denominator.checked_neg()?,
+            })
+        } else {
+            Some(Self {
+                numerator,
+                denominator,
+            })
+        }
+    }
+
+    pub const fn integer(n: i128) -> Self {
+        Self {
+            numerator: n,
+            denominator: 1,
+        }
+    }
+
+    pub fn numerator(&self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> i128 {
+        self.denominator
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denominator == 1
+    }
+
+    pub fn to_integer(&self) -> Option<i128> {
+        self.is_integer().then_some(self.numerator)
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        // Scaling by the least common multiple of the denominators keeps the values small
+        let divisor = checked_gcd(self.denominator, rhs.denominator)?;
+        let numerator = self
+            .numerator
+            .checked_mul(rhs.denominator / divisor)?
+            .checked_add(rhs.numerator.checked_mul(self.denominator / divisor)?)?;
+        Self::new(
+            numerator,
+            (self.denominator / divisor).checked_mul(rhs.denominator)?,
+        )
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.checked_add(rhs.checked_neg()?)
+    }
+
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        // Cancelling across before multiplying keeps the values small
+        let a = checked_gcd(self.numerator, rhs.denominator)?;
+        let b = checked_gcd(rhs.numerator, self.denominator)?;
+        Self::new(
+            (self.numerator / a).checked_mul(rhs.numerator / b)?,
+            (self.denominator / b).checked_mul(rhs.denominator / a)?,
+        )
+    }
+
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        self.checked_mul(Self::new(rhs.denominator, rhs.numerator)?)
+    }
+
+    pub fn checked_neg(self) -> Option<Self> {
+        Some(Self {
+            numerator: self.numerator.checked_neg()?,
+            denominator: self.denominator,
+        })
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(n: i128) -> Self {
+        Self::integer(n)
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+/// By the Bareiss algorithm, which only ever divides exactly.
+pub fn determinant<const N: usize>(matrix: &Matrix<i128, N>) -> Option<i128> {
+    let mut m = *matrix;
+    let mut negate = false;
+    let mut previous = 1;
+
+    for k in 0..N {
+        if m[k][k] == 0 {
+            match (k + 1..N).find(|&i| m[i][k] != 0) {
+                Some(i) => {
+                    m.swap(i, k);
+                    negate = !negate;
+                }
+                None => return Some(0),
+            }
+        }
+
+        for i in k + 1..N {
+            for j in k + 1..N {
+                let product = m[i][j]
+                    .checked_mul(m[k][k])?
+                    .checked_sub(m[i][k].checked_mul(m[k][j])?)?;
+                m[i][j] = product / previous;
+            }
+        }
+        previous = m[k][k];
+    }
+
+    if negate {
+        previous.checked_neg()
+    } else {
+        Some(previous)
+    }
+}
+
+/// Solves `a * x = b` by Cramer's rule, `None` if there isn't a unique solution.
+pub fn cramer<const N: usize>(a: &Matrix<i128, N>, b: &[i128; N]) -> Option<[Rational; N]> {
+    let det = determinant(a)?;
+    if det == 0 {
+        return None;
+    }
+
+    let mut x = [Rational::ZERO; N];
+    for (column, value) in x.iter_mut().enumerate() {
+        let mut replaced = *a;
+        for (row, &b) in replaced.iter_mut().zip(b) {
+            row[column] = b;
+        }
+        *value = Rational::new(determinant(&replaced)?, det)?;
+    }
+    Some(x)
+}
+
+/// Solves `a * x = b` by Gauss-Jordan elimination, `None` if there isn't a unique solution.
+pub fn solve(a: &[Vec<i128>], b: &[i128]) -> Option<Vec<Rational>> {
+    let n = b.len();
+    if a.len() != n || a.iter().any(|row| row.len() != n) {
+        return None;
+    }
+
+    let mut m: Vec<Vec<Rational>> = a
+        .iter()
+        .zip(b)
+        .map(|(row, &b)| row.iter().chain([&b]).map(|&v| v.into()).collect())
+        .collect();
+
+    for column in 0..n {
+        let pivot = (column..n).find(|&i| m[i][column] != Rational::ZERO)?;
+        m.swap(pivot, column);
+
+        let divisor = m[column][column];
+        for v in &mut m[column][column..] {
+            *v = v.checked_div(divisor)?;
+        }
+
+        let pivot_row = m[column].clone();
+        for (i, row) in m.iter_mut().enumerate() {
+            let factor = row[column];
+            if i == column || factor == Rational::ZERO {
+                continue;
+            }
+            for (v, &p) in row[column..].iter_mut().zip(&pivot_row[column..]) {
+                *v = v.checked_sub(factor.checked_mul(p)?)?;
+            }
+        }
+    }
+
+    Some(m.into_iter().map(|row| row[n]).collect())
+}
+
+/// Like [`solve`], but `None` unless every value is an integer.
+pub fn solve_integer(a: &[Vec<i128>], b: &[i128]) -> Option<Vec<i128>> {
+    solve(a, b)?.iter().map(Rational::to_integer).collect()
+}
+
+pub fn identity<T: Integer, const N: usize>() -> Matrix<T, N> {
+    std::array::from_fn(|i| std::array::from_fn(|j| if i == j { T::ONE } else { T::ZERO }))
+}
+
+pub fn checked_mul_matrix<T: Integer, const N: usize>(
+    a: &Matrix<T, N>,
+    b: &Matrix<T, N>,
+) -> Option<Matrix<T, N>> {
+    let mut product = [[T::ZERO; N]; N];
+    for (i, row) in product.iter_mut().enumerate() {
+        for (j, v) in row.iter_mut().enumerate() {
+            *v = (0..N).try_fold(T::ZERO, |sum, k| {
+                sum.checked_add(a[i][k].checked_mul(b[k][j])?)
+            })?;
+        }
+    }
+    Some(product)
+}
+
+pub fn mul_mod_matrix<T: Integer, const N: usize>(
+    a: &Matrix<T, N>,
+    b: &Matrix<T, N>,
+    modulus: T,
+) -> Matrix<T, N> {
+    std::array::from_fn(|i| {
+        std::array::from_fn(|j| {
+            (0..N).fold(T::ZERO, |sum, k| {
+                add_mod(sum, mul_mod(a[i][k], b[k][j], modulus), modulus)
+            })
+        })
+    })
+}
+
+pub fn matrix_pow<T: Integer, const N: usize>(
+    matrix: &Matrix<T, N>,
+    exponent: u64,
+) -> Option<Matrix<T, N>> {
+    let mut base = *matrix;
+    let mut exponent = exponent;
+    let mut power = identity();
+
+    while exponent > 0 {
+        if exponent % 2 == 1 {
+            power = checked_mul_matrix(&power, &base)?;
+        }
+        exponent /= 2;
+        // Squaring once more than needed could overflow when the result wouldn't
+        if exponent > 0 {
+            base = checked_mul_matrix(&base, &base)?;
+        }
+    }
+    Some(power)
+}
+
+pub fn matrix_pow_mod<T: Integer, const N: usize>(
+    matrix: &Matrix<T, N>,
+    exponent: u64,
+    modulus: T,
+) -> Matrix<T, N> {
+    let mut base = matrix.map(|row| row.map(|v| v.rem_euclid(modulus)));
+    let mut exponent = exponent;
+    let mut power = identity::<T, N>().map(|row| row.map(|v| v % modulus));
+
+    while exponent > 0 {
+        if exponent % 2 == 1 {
+            power = mul_mod_matrix(&power, &base, modulus);
+        }
+        base = mul_mod_matrix(&base, &base, modulus);
+        exponent /= 2;
+    }
+    power
+}
+
+fn companion<T: Integer, const N: usize>(coefficients: &[T; N]) -> Matrix<T, N> {
+    let mut matrix = [[T::ZERO; N]; N];
+    if let Some(first) = matrix.first_mut() {
+        *first = *coefficients;
+    }
+    for i in 1..N {
+        matrix[i][i - 1] = T::ONE;
+    }
+    matrix
+}
+
+/// Term `n` of `x[k] = c[0] * x[k - 1] + ... + c[N - 1] * x[k - N]`. Panics if `N` is 0.
+pub fn recurrence<T: Integer, const N: usize>(
+    coefficients: &[T; N],
+    initial: &[T; N],
+    n: u64,
+) -> Option<T> {
+    if let Some(&x) = usize::try_from(n).ok().and_then(|n| initial.get(n)) {
+        return Some(x);
+    }
+
+    let power = matrix_pow(&companion(coefficients), n - N as u64 + 1)?;
+    power[0]
+        .iter()
+        .zip(initial.iter().rev())
+        .try_fold(T::ZERO, |x, (&c, &v)| x.checked_add(c.checked_mul(v)?))
+}
+
+/// Like [`recurrence`], mod `modulus`.
+pub fn recurrence_mod<T: Integer, const N: usize>(
+    coefficients: &[T; N],
+    initial: &[T; N],
+    n: u64,
+    modulus: T,
+) -> T {
+    if let Some(&x) = usize::try_from(n).ok().and_then(|n| initial.get(n)) {
+        return x.rem_euclid(modulus);
+    }
+
+    let power = matrix_pow_mod(&companion(coefficients), n - N as u64 + 1, modulus);
+    power[0]
+        .iter()
+        .zip(initial.iter().rev())
+        .fold(T::ZERO, |x, (&c, &v)| {
+            add_mod(x, mul_mod(c, v, modulus), modulus)
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::generate::Rng;
+
+    fn rational(numerator: i128, denominator: i128) -> Rational {
+        Rational::new(numerator, denominator).unwrap()
+    }
+
+    fn laplace(matrix: &[Vec<i128>]) -> i128 {
+        if matrix.is_empty() {
+            return 1;
+        }
+
+        (0..matrix.len())
+            .map(|column| {
+                let minor: Vec<Vec<i128>> = matrix[1..]
+                    .iter()
+                    .map(|row| {
+                        row.iter()
+                            .enumerate()
+                            .filter(|&(j, _)| j != column)
+                            .map(|(_, &v)| v)
+                            .collect()
+                    })
+                    .collect();
+                let sign = if column % 2 == 0 { 1 } else { -1 };
+                sign * matrix[0][column] * laplace(&minor)
+            })
+            .sum()
+    }
+
+    fn random_matrix<const N: usize>(rng: &mut Rng) -> Matrix<i128, N> {
+        // Small values make singular matrices common enough to test
+        std::array::from_fn(|_| std::array::from_fn(|_| rng.range(0, 7) as i128 - 3))
+    }
+
+    #[test]
+    fn test_rational() {
+        assert_eq!(rational(1, 2), rational(-3, -6));
+        assert_eq!(-2, rational(4, -6).numerator());
+        assert_eq!(3, rational(4, -6).denominator());
+        assert_eq!(None, Rational::new(1, 0));
+        assert_eq!(None, Rational::new(1, i128::MIN));
+        assert_eq!(None, Rational::new(i128::MIN, -1));
+        assert_eq!(
+            Some(Rational::integer(1 << 126)),
+            Rational::new(i128::MIN, -2)
+        );
+
+        let (a, b) = (rational(1, 6), rational(3, 4));
+        assert_eq!(Some(rational(11, 12)), a.checked_add(b));
+        assert_eq!(Some(rational(-7, 12)), a.checked_sub(b));
+        assert_eq!(Some(rational(1, 8)), a.checked_mul(b));
+        assert_eq!(Some(rational(2, 9)), a.checked_div(b));
+        assert_eq!(None, a.checked_div(Rational::ZERO));
+        assert_eq!(Some(Rational::ZERO), Rational::ZERO.checked_mul(b));
+
+        // Cancelling first avoids overflowing on the way to a representable result
+        let big = rational(i128::MAX, 2);
+        assert_eq!(
+            Some(Rational::integer(i128::MAX)),
+            big.checked_mul(2.into())
+        );
+        assert_eq!(None, big.checked_mul(3.into()));
+
+        assert_eq!(Some(4), rational(8, 2).to_integer());
+        assert_eq!(None, rational(8, 3).to_integer());
+        assert_eq!("-8/3", rational(8, -3).to_string());
+        assert_eq!("4", rational(8, 2).to_string());
+    }
+
+    #[test]
+    fn test_determinant() {
+        let mut rng = Rng::new(50);
+        for _ in 0..200 {
+            let m = random_matrix::<4>(&mut rng);
+            let rows: Vec<Vec<i128>> = m.iter().map(|row| row.to_vec()).collect();
+            assert_eq!(Some(laplace(&rows)), determinant(&m), "{m:?}");
+        }
+
+        assert_eq!(Some(1), determinant::<0>(&[]));
+        assert_eq!(Some(-7), determinant(&[[-7]]));
+        assert_eq!(Some(-2), determinant(&[[0, 1], [2, 3]]));
+        assert_eq!(Some(0), determinant(&[[1, 2, 3], [4, 5, 6], [7, 8, 9]]));
+
+        let big = i128::MAX / 2;
+        assert_eq!(Some(0), determinant(&[[big, 2], [big, 2]]));
+        assert_eq!(None, determinant(&[[big, 1], [1, big]]));
+    }
+
+    #[test]
+    fn test_solve() {
+        let mut rng = Rng::new(50);
+        for _ in 0..200 {
+            let a = random_matrix::<3>(&mut rng);
+            let b: [i128; 3] = std::array::from_fn(|_| rng.range(0, 21) as i128 - 10);
+            let rows: Vec<Vec<i128>> = a.iter().map(|row| row.to_vec()).collect();
+
+            let x = cramer(&a, &b);
+            assert_eq!(x.map(Vec::from), solve(&rows, &b), "{a:?} {b:?}");
+            assert_eq!(determinant(&a) == Some(0), x.is_none());
+
+            if let Some(x) = x {
+                for (row, &b) in a.iter().zip(&b) {
+                    let sum = row.iter().zip(&x).fold(Rational::ZERO, |sum, (&a, &x)| {
+                        sum.checked_add(x.checked_mul(a.into()).unwrap()).unwrap()
+                    });
+                    assert_eq!(Rational::integer(b), sum);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_claw_machines() {
+        // Button A moves the claw by the first column, button B by the second
+        let presses = |a: [i128; 2], b: [i128; 2], prize: [i128; 2]| {
+            let buttons = [[a[0], b[0]], [a[1], b[1]]];
+            let x = cramer(&buttons, &prize)?;
+            Some([x[0].to_integer()?, x[1].to_integer()?])
+        };
+
+        assert_eq!(Some([80, 40]), presses([94, 34], [22, 67], [8400, 5400]));
+        assert_eq!(None, presses([26, 66], [67, 21], [12748, 12176]));
+        assert_eq!(Some([38, 86]), presses([17, 86], [84, 37], [7870, 6450]));
+        assert_eq!(None, presses([69, 23], [27, 71], [18641, 10279]));
+
+        let far = 10_000_000_000_000;
+        assert_eq!(
+            Some([118679050709, 103199174542]),
+            presses([26, 66], [67, 21], [12748 + far, 12176 + far])
+        );
+
+        assert_eq!(
+            Some(vec![80, 40]),
+            solve_integer(&[vec![94, 22], vec![34, 67]], &[8400, 5400])
+        );
+        assert_eq!(None, solve_integer(&[vec![1, 2], vec![2, 4]], &[3, 6]));
+        assert_eq!(None, solve_integer(&[vec![1, 2]], &[3]));
+    }
+
+    #[test]
+    fn test_matrix_pow() {
+        let fibonacci = [[1u64, 1], [1, 0]];
+        assert_eq!(Some(identity()), matrix_pow(&fibonacci, 0));
+        assert_eq!(Some([[89, 55], [55, 34]]), matrix_pow(&fibonacci, 10));
+        assert_eq!(
+            [[89 % 7, 55 % 7], [55 % 7, 34 % 7]],
+            matrix_pow_mod(&fibonacci, 10, 7)
+        );
+        assert_eq!([[0, 0], [0, 0]], matrix_pow_mod(&fibonacci, 0, 1));
+
+        let m = [[2i64, -1, 0], [1, 3, -2], [0, 4, 1]];
+        let mut expected = identity();
+        for n in 0..20 {
+            assert_eq!(Some(expected), matrix_pow(&m, n), "{n}");
+            assert_eq!(
+                expected.map(|row| row.map(|v| v.rem_euclid(1009))),
+                matrix_pow_mod(&m, n, 1009),
+                "{n}"
+            );
+            expected = checked_mul_matrix(&expected, &m).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_recurrence() {
+        // Fibonacci up to and past the last term that fits
+        let (mut a, mut b) = (Some(0u128), Some(1u128));
+        for n in 0..200 {
+            assert_eq!(a, recurrence(&[1, 1], &[0, 1], n), "{n}");
+            (a, b) = (b, a.zip(b).and_then(|(a, b)| a.checked_add(b)));
+        }
+
+        // Tribonacci with a negative coefficient, mod a prime
+        let modulus = 1_000_000_007i64;
+        let mut terms = vec![3, 1, 4];
+        for n in 0..1000 {
+            if n >= 3 {
+                let k = terms.len();
+                let next = 2 * terms[k - 1] - terms[k - 2] + 5 * terms[k - 3];
+                terms.push(next % modulus);
+            }
+            assert_eq!(
+                terms[n].rem_euclid(modulus),
+                recurrence_mod(&[2, -1, 5], &[3, 1, 4], n as u64, modulus),
+                "{n}"
+            );
+        }
+
+        // Far along, the sequence cycles mod 10 every 60 terms
+        let far = 60 * 1_000_000_000_000_000 + 7;
+        assert_eq!(3, recurrence_mod(&[1u64, 1], &[0, 1], far, 10));
+    }
+}
@@ -0,0 +1,398 @@
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+pub trait Integer:
+    Copy
+    + Ord
+    + Debug
+    + Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const TWO: Self;
+    const TEN: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_rem(self, rhs: Self) -> Option<Self>;
+    fn rem_euclid(self, rhs: Self) -> Self;
+    fn isqrt(self) -> Self;
+    fn as_f64(self) -> f64;
+}
+
+pub trait Signed: Integer + Neg<Output = Self> {}
+
+macro_rules! integer {
+    ($($t:ty)*) => {
+        $(
+            impl Integer for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                const TWO: Self = 2;
+                const TEN: Self = 10;
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
+                }
+
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
+
+                fn checked_rem(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_rem(self, rhs)
+                }
+
+                fn rem_euclid(self, rhs: Self) -> Self {
+                    <$t>::rem_euclid(self, rhs)
+                }
+
+                fn isqrt(self) -> Self {
+                    <$t>::isqrt(self)
+                }
+
+                fn as_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+integer!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize);
+
+impl Signed for i8 {}
+impl Signed for i16 {}
+impl Signed for i32 {}
+impl Signed for i64 {}
+impl Signed for i128 {}
+impl Signed for isize {}
+
+/// Panics if the result would be `-MIN`, see [`checked_gcd`].
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    checked_gcd(a, b).expect("Greatest common divisor overflowed")
+}
+
+/// Never negative, or `None` if it overflows.
+pub fn checked_gcd<T: Integer>(a: T, b: T) -> Option<T> {
+    let (mut a, mut b) = (a, b);
+    while b != T::ZERO {
+        // Only MIN % -1 overflows, and is 0
+        (a, b) = (b, a.checked_rem(b).unwrap_or(T::ZERO));
+    }
+
+    if a < T::ZERO {
+        T::ZERO.checked_sub(a)
+    } else {
+        Some(a)
+    }
+}
+
+/// 0 if there are no numbers.
+pub fn gcd_all<T: Integer>(numbers: impl IntoIterator<Item = T>) -> T {
+    numbers.into_iter().fold(T::ZERO, gcd)
+}
+
+/// Never negative, or `None` if it overflows.
+pub fn lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+
+    let multiple = (a / checked_gcd(a, b)?).checked_mul(b)?;
+    if multiple < T::ZERO {
+        T::ZERO.checked_sub(multiple)
+    } else {
+        Some(multiple)
+    }
+}
+
+/// 1 if there are no numbers, or `None` if it overflows.
+pub fn lcm_all<T: Integer>(numbers: impl IntoIterator<Item = T>) -> Option<T> {
+    numbers
+        .into_iter()
+        .try_fold(T::ONE, |multiple, n| lcm(multiple, n))
+}
+
+/// Returns `(gcd, x, y)` such that `a * x + b * y = gcd`.
+pub fn extended_gcd<T: Signed>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::ONE, T::ZERO);
+    let (mut old_y, mut y) = (T::ZERO, T::ONE);
+
+    while r != T::ZERO {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+
+    if old_r < T::ZERO {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// `x` in `0..modulus` such that `a * x ≡ 1 (mod modulus)`.
+pub fn mod_inverse<T: Signed>(a: T, modulus: T) -> Option<T> {
+    if modulus <= T::ZERO {
+        return None;
+    }
+
+    match extended_gcd(a.rem_euclid(modulus), modulus) {
+        (gcd, x, _) if gcd == T::ONE => Some(x.rem_euclid(modulus)),
+        _ => None,
+    }
+}
+
+pub(crate) fn add_mod<T: Integer>(a: T, b: T, modulus: T) -> T {
+    debug_assert!(T::ZERO <= a && a < modulus && T::ZERO <= b && b < modulus);
+    if a >= modulus - b {
+        a - (modulus - b)
+    } else {
+        a + b
+    }
+}
+
+/// Doesn't overflow even when `a * b` would.
+pub fn mul_mod<T: Integer>(a: T, b: T, modulus: T) -> T {
+    let (mut a, mut b) = (a.rem_euclid(modulus), b.rem_euclid(modulus));
+    if let Some(product) = a.checked_mul(b) {
+        return product % modulus;
+    }
+
+    // Doubling and adding, which only ever needs values below the modulus
+    let mut product = T::ZERO;
+    while b > T::ZERO {
+        if b % T::TWO == T::ONE {
+            product = add_mod(product, a, modulus);
+        }
+        a = add_mod(a, a, modulus);
+        b = b / T::TWO;
+    }
+    product
+}
+
+/// `exponent` mustn't be negative.
+pub fn mod_pow<T: Integer>(base: T, exponent: T, modulus: T) -> T {
+    let mut base = base.rem_euclid(modulus);
+    let mut exponent = exponent;
+    let mut power = T::ONE % modulus;
+
+    while exponent > T::ZERO {
+        if exponent % T::TWO == T::ONE {
+            power = mul_mod(power, base, modulus);
+        }
+        base = mul_mod(base, base, modulus);
+        exponent = exponent / T::TWO;
+    }
+    power
+}
+
+/// Solves `x ≡ residue (mod modulus)` for every pair, returning `(x, m)` where `m` is the least
+/// common multiple of the moduli, which needn't be coprime.
+pub fn crt<T: Signed>(congruences: impl IntoIterator<Item = (T, T)>) -> Option<(T, T)> {
+    congruences
+        .into_iter()
+        .try_fold((T::ZERO, T::ONE), |(x, m), (residue, modulus)| {
+            if modulus <= T::ZERO {
+                return None;
+            }
+            let residue = residue.rem_euclid(modulus);
+
+            // Solving x + m * k ≡ residue (mod modulus) for k
+            let divisor = gcd(m, modulus);
+            let difference = residue - x % modulus;
+            if difference % divisor != T::ZERO {
+                return None;
+            }
+
+            let reduced = modulus / divisor;
+            let inverse = mod_inverse(m / divisor, reduced)?;
+            let k = mul_mod(difference / divisor, inverse, reduced);
+
+            let combined = (m / divisor).checked_mul(modulus)?;
+            Some(((x + m * k).rem_euclid(combined), combined))
+        })
+}
+
+/// `n` mustn't be negative.
+pub fn isqrt<T: Integer>(n: T) -> T {
+    n.isqrt()
+}
+
+/// Decimal digits in `n`, ignoring any sign.
+pub fn digits<T: Integer>(n: T) -> u32 {
+    let mut n = n / T::TEN;
+    let mut digits = 1;
+    while n != T::ZERO {
+        n = n / T::TEN;
+        digits += 1;
+    }
+    digits
+}
+
+/// `concat(12, 345) == Some(12345)`, for non-negative `a` and `b`.
+pub fn concat<T: Integer>(a: T, b: T) -> Option<T> {
+    let shift = (0..digits(b)).try_fold(T::ONE, |shift, _| shift.checked_mul(T::TEN))?;
+    a.checked_mul(shift)?.checked_add(b)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(6, gcd(48, 18));
+        assert_eq!(6, gcd(18u8, 48));
+        assert_eq!(6, gcd(-48, 18));
+        assert_eq!(5, gcd(0, -5));
+        assert_eq!(0, gcd(0, 0));
+        assert_eq!(1, gcd(i64::MIN, -1));
+        assert_eq!(1 << 62, gcd(i64::MIN, 1 << 62));
+        assert_eq!(None, checked_gcd(i64::MIN, 0));
+        assert_eq!(Some(i64::MAX), checked_gcd(i64::MAX, 0));
+
+        assert_eq!(Some(144), lcm(48, 18));
+        assert_eq!(Some(144), lcm(-48, 18));
+        assert_eq!(Some(0), lcm(0, 7));
+        assert_eq!(None, lcm(200u8, 3));
+        assert_eq!(Some(255), lcm(255u8, 85));
+
+        assert_eq!(4, gcd_all([12, 8, 20]));
+        assert_eq!(0, gcd_all(Vec::<u32>::new()));
+        assert_eq!(Some(2520), lcm_all(1..=10u64));
+        assert_eq!(Some(1), lcm_all(Vec::<u32>::new()));
+    }
+
+    #[test]
+    fn test_large_gcd_lcm() {
+        // Consecutive Fibonacci numbers take the most steps
+        let (a, b) = (
+            190392490709135u128 * 1_000_000_007,
+            308061521170129u128 * 1_000_000_007,
+        );
+        assert_eq!(1_000_000_007, gcd(a, b));
+
+        let big = u128::MAX / 3;
+        assert_eq!(big, gcd(big, big * 2));
+        assert_eq!(Some(big * 2), lcm(big, big * 2));
+        assert_eq!(None, lcm(big, big - 1));
+        assert_eq!(None, lcm_all([u64::MAX, u64::MAX - 1]));
+        assert_eq!(None, lcm(i128::MIN, 3));
+        assert_eq!(None, lcm(i128::MIN, i128::MIN));
+        assert_eq!(
+            Some(2u128.pow(64) * 3 * 5 * 7),
+            lcm_all([2u128.pow(64), 3, 5, 7, 2u128.pow(10)])
+        );
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        for (a, b) in [(240, 46), (-240, 46), (46, -240), (0, 5), (17, 0), (1, 1)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(gcd(a, b), g, "{a} {b}");
+            assert_eq!(g, a * x + b * y, "{a} {b}");
+        }
+
+        let (a, b) = (i64::MAX, i64::MAX - 1);
+        let (g, x, y) = extended_gcd(a, b);
+        assert_eq!(1, g);
+        assert_eq!(1, a as i128 * x as i128 + b as i128 * y as i128);
+
+        assert_eq!(Some(4), mod_inverse(3, 11));
+        assert_eq!(Some(7), mod_inverse(-3, 11));
+        assert_eq!(None, mod_inverse(6, 9));
+        assert_eq!(None, mod_inverse(3, 0));
+        let p = 1_000_000_007i64;
+        assert_eq!(1, mul_mod(mod_inverse(123456789, p).unwrap(), 123456789, p));
+    }
+
+    #[test]
+    fn test_mod_pow() {
+        assert_eq!(445, mod_pow(4, 13, 497));
+        assert_eq!(0, mod_pow(4, 13, 1));
+        assert_eq!(1, mod_pow(0, 0, 7));
+        assert_eq!(6, mod_pow(-4, 3, 10));
+
+        // Fermat's little theorem for the largest prime below 2^64, where squaring overflows
+        let p = 18446744073709551557u64;
+        assert_eq!(1, mod_pow(123456789, p - 1, p));
+        assert_eq!(p - 1, mul_mod(p - 1, 1, p));
+        assert_eq!(1, mul_mod(p - 1, p - 1, p));
+
+        let p = u128::MAX - 158; // 2^128 - 159 is prime
+        assert_eq!(1, mod_pow(2, p - 1, p));
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(Some((23, 105)), crt([(2, 3), (3, 5), (2, 7)]));
+        assert_eq!(Some((0, 1)), crt(Vec::<(i32, i32)>::new()));
+        assert_eq!(Some((59, 60)), crt([(-1, 4), (-1, 6), (4, 5)]));
+        assert_eq!(None, crt([(1, 4), (2, 6)]));
+        assert_eq!(None, crt([(1, 0)]));
+
+        // Bus schedule style moduli whose product needs more than 64 bits
+        let congruences = [
+            (0, 1789i128),
+            (-1, 37),
+            (-2, 47),
+            (-3, 1889),
+            (-7, 1_000_000_007),
+            (-11, 998_244_353),
+        ];
+        let (x, m) = crt(congruences).unwrap();
+        assert_eq!(1789 * 37 * 47 * 1889 * 1_000_000_007 * 998_244_353, m);
+        for (residue, modulus) in congruences {
+            assert_eq!(residue.rem_euclid(modulus), x % modulus);
+        }
+
+        assert_eq!(None, crt([(0, i64::MAX), (0, i64::MAX - 1)]));
+    }
+
+    #[test]
+    fn test_isqrt() {
+        assert_eq!(0, isqrt(0));
+        assert_eq!(3, isqrt(15));
+        assert_eq!(4, isqrt(16));
+        assert_eq!(u64::MAX as u128, isqrt(u128::MAX));
+        assert_eq!(3037000499, isqrt(i64::MAX));
+    }
+
+    #[test]
+    fn test_digits_concat() {
+        assert_eq!(1, digits(0));
+        assert_eq!(1, digits(9u8));
+        assert_eq!(2, digits(10u8));
+        assert_eq!(3, digits(255u8));
+        assert_eq!(3, digits(-128i8));
+        assert_eq!(20, digits(u64::MAX));
+        assert_eq!(39, digits(u128::MAX));
+
+        assert_eq!(Some(12345), concat(12, 345));
+        assert_eq!(Some(156), concat(15, 6));
+        assert_eq!(Some(100), concat(10, 0));
+        assert_eq!(Some(5), concat(0, 5));
+        assert_eq!(Some(255), concat(25u8, 5));
+        assert_eq!(None, concat(25u8, 6));
+        assert_eq!(None, concat(3u8, 55));
+        assert_eq!(
+            Some(1844674407370955161512345u128),
+            concat(u64::MAX as u128, 12345)
+        );
+        assert_eq!(None, concat(u128::MAX / 10, 10));
+    }
+}
@@ -0,0 +1,145 @@
+//! Caching the results of recursive functions with overlapping subproblems, keeping count of how
+//! often the cache helped.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::hash::Hash;
+
+/// A cache from arguments to results. A recursive function takes the memo as an argument and wraps
+/// its body in [`Memo::get_or_compute`], which passes the memo back for the recursive calls:
+///
+/// ```
+/// use util::memo::Memo;
+///
+/// fn fibonacci(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+///     memo.get_or_compute(n, |memo| match n {
+///         0 | 1 => n,
+///         _ => fibonacci(memo, n - 1) + fibonacci(memo, n - 2),
+///     })
+/// }
+///
+/// assert_eq!(12586269025, fibonacci(&mut Memo::new(), 50));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    hits: usize,
+    misses: usize,
+}
+
+impl<K: Hash + Eq, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self {
+            cache: HashMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// The cached result for `key`, or the result of `compute`, which is cached.
+    pub fn get_or_compute(&mut self, key: K, compute: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            self.hits += 1;
+            return value.clone();
+        }
+
+        self.misses += 1;
+        let value = compute(self);
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    /// Forgets every cached result, but not the stats, for reusing the memo on an unrelated problem.
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+
+    pub fn stats(&self) -> Stats {
+        Stats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.cache.len(),
+        }
+    }
+}
+
+impl<K: Hash + Eq, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How a [`Memo`] has been used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    /// Lookups answered from the cache.
+    pub hits: usize,
+    /// Lookups that had to be computed.
+    pub misses: usize,
+    /// Results currently cached.
+    pub entries: usize,
+}
+
+impl Stats {
+    /// Fraction of lookups answered from the cache, 0 if there haven't been any.
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            lookups => self.hits as f64 / lookups as f64,
+        }
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} hits, {} misses ({:.1}% hit rate), {} entries",
+            self.hits,
+            self.misses,
+            100.0 * self.hit_rate(),
+            self.entries
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn fibonacci(memo: &mut Memo<u64, u128>, n: u64) -> u128 {
+        memo.get_or_compute(n, |memo| match n {
+            0 | 1 => n as u128,
+            _ => fibonacci(memo, n - 1) + fibonacci(memo, n - 2),
+        })
+    }
+
+    #[test]
+    fn test_memo() {
+        let mut memo = Memo::new();
+        assert_eq!(0.0, memo.stats().hit_rate());
+
+        assert_eq!(2880067194370816120, fibonacci(&mut memo, 90));
+        // Every n from 0 to 90 is computed once, and from 3 up the second recursive call hits
+        assert_eq!(
+            Stats {
+                hits: 88,
+                misses: 91,
+                entries: 91
+            },
+            memo.stats()
+        );
+
+        assert_eq!(1548008755920, fibonacci(&mut memo, 60));
+        assert_eq!(89, memo.stats().hits);
+
+        memo.clear();
+        assert_eq!(0, memo.stats().entries);
+        assert_eq!(89, memo.stats().hits);
+        assert_eq!(55, fibonacci(&mut memo, 10));
+        assert_eq!(
+            "97 hits, 102 misses (48.7% hit rate), 11 entries",
+            memo.stats().to_string()
+        );
+    }
+}
@@ -0,0 +1,288 @@
+//! Helpers for the formats that come up repeatedly in puzzle input. Errors say what was expected
+//! and, where it helps, the 1-based column it was found at.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+
+use crate::input::column_in;
+use crate::{Line, LocationContext};
+
+/// Parses a single, possibly signed, number, ignoring surrounding whitespace.
+pub fn number<T>(s: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let trimmed = s.trim();
+    T::from_str(trimmed).map_err(|e| anyhow!("Invalid number {trimmed:?}: {e}"))
+}
+
+/// Parses whitespace-separated numbers, failing on anything that isn't a number.
+pub fn numbers<T>(s: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    numbers_within(s, s)
+}
+
+/// Like [`numbers`], where `s` is a slice of `line` and columns are relative to `line`.
+fn numbers_within<T>(s: &str, line: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    s.split_ascii_whitespace()
+        .map(|token| number_within(token, line))
+        .collect()
+}
+
+/// Like [`number`], where `s` is a slice of `line` and the error gives its column in `line`.
+fn number_within<T>(s: &str, line: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let trimmed = s.trim();
+    T::from_str(trimmed).map_err(|e| match column_in(line, trimmed) {
+        Some(column) => anyhow!("Invalid number {trimmed:?} at column {column}: {e}"),
+        None => anyhow!("Invalid number {trimmed:?}: {e}"),
+    })
+}
+
+/// Extracts every integer from `s`, ignoring whatever separates them. A `-` immediately before
+/// digits is treated as a sign, so `T` should be signed if the input can contain one.
+pub fn integers<T>(s: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let bytes = s.as_bytes();
+    let mut integers = Vec::new();
+
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let start = pos;
+        if bytes[pos] == b'-' && bytes.get(pos + 1).is_some_and(u8::is_ascii_digit) {
+            pos += 1;
+        }
+
+        if !bytes[pos].is_ascii_digit() {
+            pos += 1;
+            continue;
+        }
+
+        while bytes.get(pos).is_some_and(u8::is_ascii_digit) {
+            pos += 1;
+        }
+
+        let token = &s[start..pos];
+        integers.push(
+            T::from_str(token)
+                .map_err(|e| anyhow!("Invalid integer {token:?} at column {}: {e}", start + 1))?,
+        );
+    }
+
+    Ok(integers)
+}
+
+/// Splits `s` at the first `separator` and parses each side, ignoring surrounding whitespace. A
+/// whitespace separator such as `" "` instead splits on any run of ASCII whitespace, including
+/// tabs, and requires exactly two values.
+pub fn separated_pair<A, B>(s: &str, separator: &str) -> Result<(A, B)>
+where
+    A: FromStr,
+    A::Err: Display,
+    B: FromStr,
+    B::Err: Display,
+{
+    let expected = || anyhow!("Expected two values separated by {separator:?} in {s:?}");
+
+    let (lhs, rhs) = if separator.trim().is_empty() {
+        let mut values = s.split_ascii_whitespace();
+        match (values.next(), values.next(), values.next()) {
+            (Some(lhs), Some(rhs), None) => (lhs, rhs),
+            _ => return Err(expected()),
+        }
+    } else {
+        s.split_once(separator).ok_or_else(expected)?
+    };
+
+    Ok((number_within(lhs, s)?, number_within(rhs, s)?))
+}
+
+/// Parses a line of the form `key<separator> n1 n2 ...`, e.g. `190: 10 19`.
+pub fn key_list<K, V>(s: &str, separator: &str) -> Result<(K, Vec<V>)>
+where
+    K: FromStr,
+    K::Err: Display,
+    V: FromStr,
+    V::Err: Display,
+{
+    let (key, list) = s
+        .split_once(separator)
+        .ok_or_else(|| anyhow!("Expected {separator:?} after the key in {s:?}"))?;
+
+    Ok((number_within(key, s)?, numbers_within(list, s)?))
+}
+
+/// Parses a grid of cells, one per character, requiring every row to have the same width. Errors
+/// include the line and column of the offending cell.
+pub fn grid<'a, T>(
+    lines: impl IntoIterator<Item = Line<'a>>,
+    mut cell: impl FnMut(char) -> Result<T>,
+) -> Result<Vec<Vec<T>>> {
+    let mut grid: Vec<Vec<T>> = Vec::new();
+
+    for line in lines {
+        let mut row = Vec::with_capacity(line.text.len());
+        for (x, c) in line.text.chars().enumerate() {
+            row.push(cell(c).at(line.number, x + 1)?);
+        }
+
+        if let Some(width) = grid.first().map(Vec::len) {
+            if row.len() != width {
+                return Err(anyhow!("Expected {width} cells but found {}", row.len()))
+                    .at_line(line.number);
+            }
+        }
+
+        grid.push(row);
+    }
+
+    Ok(grid)
+}
+
+#[cfg(test)]
+mod test {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::Input;
+
+    #[test]
+    fn test_numbers() -> Result<()> {
+        assert_eq!(-12, number::<i64>(" -12 ")?);
+        assert_eq!(vec![7, 6, 4, 2, 1], numbers::<usize>("7 6  4 2\t1")?);
+        assert!(numbers::<usize>("").unwrap().is_empty());
+        assert_eq!(
+            "Invalid number \"x\" at column 5: invalid digit found in string",
+            numbers::<usize>("4 5 x 6").unwrap_err().to_string()
+        );
+        assert!(numbers::<u8>("256").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_integers() -> Result<()> {
+        assert_eq!(vec![-3, 4, 10, -2], integers::<i32>("p=-3,4 v=10,-2")?);
+        assert_eq!(vec![5, 5], integers::<u32>("mul(5,5)-")?);
+        assert_eq!(
+            "Invalid integer \"-1\" at column 4: invalid digit found in string",
+            integers::<u32>("x: -1").unwrap_err().to_string()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pairs_and_lists() -> Result<()> {
+        assert_eq!((3, 4), separated_pair::<usize, usize>("3   4", " ")?);
+        assert_eq!((3, 4), separated_pair::<usize, usize>("3\t4", " ")?);
+        assert_eq!((3, 4), separated_pair::<usize, usize>(" 3 \t 4\t", " ")?);
+        assert_eq!((47, 53), separated_pair::<usize, usize>("47|53", "|")?);
+        assert!(separated_pair::<usize, usize>("3 4 5", " ").is_err());
+        assert!(separated_pair::<usize, usize>("3", " ").is_err());
+        assert_eq!(
+            "Invalid number \"x\" at column 4: invalid digit found in string",
+            separated_pair::<usize, usize>("47|x", "|")
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "Invalid number \"-4\" at column 5: invalid digit found in string",
+            separated_pair::<usize, usize>("3\t  -4", " ")
+                .unwrap_err()
+                .to_string()
+        );
+
+        assert_eq!(
+            (3267, vec![81, 40, 27]),
+            key_list::<usize, usize>("3267: 81 40 27", ":")?
+        );
+        assert!(key_list::<usize, usize>("3267 81 40 27", ":").is_err());
+        assert_eq!(
+            "Invalid number \"81:\" at column 7: invalid digit found in string",
+            key_list::<usize, usize>("3267: 81: 40", ":")
+                .unwrap_err()
+                .to_string()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_grid() -> Result<()> {
+        let cell = |c| match c {
+            '.' => Ok(false),
+            '#' => Ok(true),
+            _ => Err(anyhow!("Invalid cell {c:?}")),
+        };
+
+        let input = Input::from("..#\n#..");
+        assert_eq!(
+            vec![vec![false, false, true], vec![true, false, false]],
+            grid(input.lines(), cell)?
+        );
+
+        let input = Input::from("..#\n#.x");
+        assert_eq!(
+            "line 2, column 3: Invalid cell 'x'",
+            format!("{:#}", grid(input.lines(), cell).unwrap_err())
+        );
+
+        let input = Input::from("..#\n#.");
+        assert_eq!(
+            "line 2: Expected 3 cells but found 2",
+            format!("{:#}", grid(input.lines(), cell).unwrap_err())
+        );
+
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn numbers_round_trip(
+            values in prop::collection::vec(any::<i64>(), 0..20),
+            sep in "[ \t]{1,3}",
+        ) {
+            let s = values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(&sep);
+            prop_assert_eq!(&values, &numbers::<i64>(&s).unwrap());
+            prop_assert_eq!(&values, &integers::<i64>(&s).unwrap());
+        }
+
+        #[test]
+        fn key_list_round_trip(
+            key in any::<u32>(),
+            values in prop::collection::vec(any::<u32>(), 0..20),
+        ) {
+            let s = format!(
+                "{key}: {}",
+                values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(" ")
+            );
+            prop_assert_eq!((key, values), key_list::<u32, u32>(&s, ":").unwrap());
+        }
+
+        #[test]
+        fn arbitrary_input_does_not_panic(s in "\\PC{0,64}") {
+            let _ = numbers::<i32>(&s);
+            let _ = integers::<i32>(&s);
+            let _ = separated_pair::<i32, u8>(&s, ",");
+            let _ = key_list::<u32, i16>(&s, ":");
+            let _ = grid(Input::from(s.as_str()).lines(), Ok);
+        }
+    }
+}
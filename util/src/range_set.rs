@@ -0,0 +1,338 @@
+use std::ops::RangeInclusive;
+
+use crate::math::Integer;
+
+/// Sorted, disjoint inclusive ranges, so the set can hold the type's greatest value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeSet<T> {
+    ranges: Vec<(T, T)>,
+}
+
+fn touches<T: Integer>(end: T, start: T) -> bool {
+    end >= start || end.checked_add(T::ONE) == Some(start)
+}
+
+impl<T: Integer> RangeSet<T> {
+    pub fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        let (mut start, mut end) = range.into_inner();
+        if start > end {
+            return;
+        }
+
+        let first = self.ranges.partition_point(|&(_, e)| !touches(e, start));
+        let last = self.ranges.partition_point(|&(s, _)| touches(end, s));
+        if first < last {
+            start = start.min(self.ranges[first].0);
+            end = end.max(self.ranges[last - 1].1);
+        }
+
+        self.ranges.splice(first..last, [(start, end)]);
+    }
+
+    pub fn remove(&mut self, range: RangeInclusive<T>) {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return;
+        }
+
+        let first = self.ranges.partition_point(|&(_, e)| e < start);
+        let last = self.ranges.partition_point(|&(s, _)| s <= end);
+        if first == last {
+            return;
+        }
+
+        // Neither subtraction can overflow, as there are smaller or larger values in the set
+        let mut remaining = Vec::with_capacity(2);
+        if self.ranges[first].0 < start {
+            remaining.push((self.ranges[first].0, start - T::ONE));
+        }
+        if self.ranges[last - 1].1 > end {
+            remaining.push((end + T::ONE, self.ranges[last - 1].1));
+        }
+
+        self.ranges.splice(first..last, remaining);
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        self.extend(other.ranges());
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        union.merge(other);
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+
+        let (mut i, mut j) = (0, 0);
+        while let (Some(&(a_start, a_end)), Some(&(b_start, b_end))) =
+            (self.ranges.get(i), other.ranges.get(j))
+        {
+            let (start, end) = (a_start.max(b_start), a_end.min(b_end));
+            if start <= end {
+                ranges.push((start, end));
+            }
+
+            if a_end < b_end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        for range in other.ranges() {
+            difference.remove(range);
+        }
+        difference
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|&(_, e)| e < value);
+        self.ranges.get(i).is_some_and(|&(s, _)| s <= value)
+    }
+
+    /// True for an empty range.
+    pub fn contains_range(&self, range: RangeInclusive<T>) -> bool {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return true;
+        }
+
+        let i = self.ranges.partition_point(|&(_, e)| e < start);
+        self.ranges
+            .get(i)
+            .is_some_and(|&(s, e)| s <= start && end <= e)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// `None` if there are too many values for `T`.
+    pub fn covered(&self) -> Option<T> {
+        self.ranges.iter().try_fold(T::ZERO, |total, &(s, e)| {
+            // e - s can't overflow for unsigned types, and is at most MAX - MIN for signed ones
+            let length = e.checked_sub(s)?.checked_add(T::ONE)?;
+            total.checked_add(length)
+        })
+    }
+
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|&(s, e)| s..=e)
+    }
+
+    pub fn gaps(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges
+            .windows(2)
+            .map(|w| (w[0].1 + T::ONE)..=(w[1].0 - T::ONE))
+    }
+
+    pub fn gaps_within(
+        &self,
+        within: RangeInclusive<T>,
+    ) -> impl Iterator<Item = RangeInclusive<T>> {
+        let within = Self::from_iter([within]);
+        within
+            .difference(self)
+            .ranges
+            .into_iter()
+            .map(|(s, e)| s..=e)
+    }
+}
+
+impl<T: Integer> Default for RangeSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Integer> Extend<RangeInclusive<T>> for RangeSet<T> {
+    fn extend<I: IntoIterator<Item = RangeInclusive<T>>>(&mut self, iter: I) {
+        for range in iter {
+            self.insert(range);
+        }
+    }
+}
+
+impl<T: Integer> FromIterator<RangeInclusive<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::generate::Rng;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Bits([bool; 256]);
+
+    impl Bits {
+        fn index(value: i8) -> usize {
+            (value as i16 + 128) as usize
+        }
+
+        fn value(index: usize) -> i8 {
+            (index as i16 - 128) as i8
+        }
+
+        fn from_ranges(ranges: impl IntoIterator<Item = RangeInclusive<i8>>) -> Self {
+            let mut bits = [false; 256];
+            for range in ranges {
+                for value in range {
+                    bits[Self::index(value)] = true;
+                }
+            }
+            Self(bits)
+        }
+
+        fn zip(&self, other: &Self, f: impl Fn(bool, bool) -> bool) -> Self {
+            Self(std::array::from_fn(|i| f(self.0[i], other.0[i])))
+        }
+
+        fn ranges(&self) -> Vec<RangeInclusive<i8>> {
+            let mut ranges = Vec::new();
+            let mut start = None;
+            for i in 0..=256 {
+                match (start, self.0.get(i).copied().unwrap_or(false)) {
+                    (None, true) => start = Some(i),
+                    (Some(s), false) => {
+                        ranges.push(Self::value(s)..=Self::value(i - 1));
+                        start = None;
+                    }
+                    _ => (),
+                }
+            }
+            ranges
+        }
+    }
+
+    fn check(set: &RangeSet<i8>, bits: &Bits) {
+        assert_eq!(bits.ranges(), set.ranges().collect::<Vec<_>>());
+        for index in 0..256 {
+            assert_eq!(bits.0[index], set.contains(Bits::value(index)));
+        }
+
+        let count = bits.0.iter().filter(|&&b| b).count();
+        assert_eq!(i8::try_from(count).ok(), set.covered());
+
+        let gaps = Bits(bits.0.map(|b| !b)).ranges();
+        let inner = gaps
+            .iter()
+            .filter(|g| *g.start() != i8::MIN && *g.end() != i8::MAX)
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(inner, set.gaps().collect::<Vec<_>>());
+        assert_eq!(gaps, set.gaps_within(i8::MIN..=i8::MAX).collect::<Vec<_>>());
+    }
+
+    fn random_ranges(rng: &mut Rng) -> Vec<RangeInclusive<i8>> {
+        (0..rng.range(0, 6))
+            .map(|_| {
+                let start = rng.range(0, 256) as i16 - 128;
+                let length = rng.range(0, 40) as i16;
+                start as i8..=(start + length).min(127) as i8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_every_insert_and_remove() {
+        let base = [-128..=-120, -50..=-40, -38..=-38, 0..=10, 100..=127];
+        let set: RangeSet<i8> = base.iter().cloned().collect();
+        let bits = Bits::from_ranges(base.iter().cloned());
+        check(&set, &bits);
+
+        for start in i8::MIN..=i8::MAX {
+            for end in i8::MIN..=i8::MAX {
+                let range = Bits::from_ranges([start..=end]);
+
+                let mut inserted = set.clone();
+                inserted.insert(start..=end);
+                let expected = bits.zip(&range, |a, b| a || b);
+                assert_eq!(expected.ranges(), inserted.ranges().collect::<Vec<_>>());
+
+                let mut removed = set.clone();
+                removed.remove(start..=end);
+                let expected = bits.zip(&range, |a, b| a && !b);
+                assert_eq!(expected.ranges(), removed.ranges().collect::<Vec<_>>());
+
+                let covered = (start..=end).all(|v| bits.0[Bits::index(v)]);
+                assert_eq!(covered, set.contains_range(start..=end), "{start}..={end}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let mut rng = Rng::new(49);
+
+        for _ in 0..500 {
+            let (a, b) = (random_ranges(&mut rng), random_ranges(&mut rng));
+            let (a_set, b_set): (RangeSet<i8>, RangeSet<i8>) =
+                (a.iter().cloned().collect(), b.iter().cloned().collect());
+            let (a_bits, b_bits) = (Bits::from_ranges(a), Bits::from_ranges(b));
+
+            check(&a_set, &a_bits);
+            check(&a_set.union(&b_set), &a_bits.zip(&b_bits, |a, b| a || b));
+            check(
+                &a_set.intersection(&b_set),
+                &a_bits.zip(&b_bits, |a, b| a && b),
+            );
+            check(
+                &a_set.difference(&b_set),
+                &a_bits.zip(&b_bits, |a, b| a && !b),
+            );
+
+            let mut merged = a_set.clone();
+            merged.merge(&b_set);
+            assert_eq!(a_set.union(&b_set), merged);
+        }
+    }
+
+    #[test]
+    fn test_edges() {
+        let mut set = RangeSet::new();
+        set.insert(0..=u64::MAX);
+        assert_eq!(None, set.covered());
+        assert!(set.contains(u64::MAX));
+
+        set.remove(10..=u64::MAX - 10);
+        assert_eq!(Some(20), set.covered());
+        assert_eq!(
+            vec![0..=9, u64::MAX - 9..=u64::MAX],
+            set.ranges().collect::<Vec<_>>()
+        );
+        assert_eq!(vec![10..=u64::MAX - 10], set.gaps().collect::<Vec<_>>());
+
+        // Touching ranges are merged, and empty ones ignored
+        let set: RangeSet<i32> = [1..=3, 4..=6, 8..=9, RangeInclusive::new(5, 2)]
+            .into_iter()
+            .collect();
+        assert_eq!(vec![1..=6, 8..=9], set.ranges().collect::<Vec<_>>());
+        assert!(set.contains_range(RangeInclusive::new(9, 8)));
+        assert!(!set.contains_range(5..=8));
+        assert_eq!(
+            vec![-2..=0, 7..=7, 10..=12],
+            set.gaps_within(-2..=12).collect::<Vec<_>>()
+        );
+        assert_eq!(Some(8), set.covered());
+
+        assert!(RangeSet::<u8>::new().is_empty());
+        assert_eq!(Some(0), RangeSet::<u8>::new().covered());
+    }
+}
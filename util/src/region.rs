@@ -0,0 +1,248 @@
+//! Labelling connected regions of a dense grid and measuring their shape.
+
+use crate::search::bfs;
+use crate::stats::BoundingBox;
+use crate::Direction;
+
+/// One connected region of a grid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    /// `(x, y)` positions in the region, in the order they were reached from the first.
+    pub positions: Vec<(usize, usize)>,
+    /// Count of cell edges bordering something outside the region, including the grid's edges.
+    pub perimeter: usize,
+    /// Count of straight runs of perimeter edges.
+    pub sides: usize,
+    /// Top left and bottom right positions, inclusive.
+    pub bounds: ((usize, usize), (usize, usize)),
+}
+
+impl Region {
+    pub fn area(&self) -> usize {
+        self.positions.len()
+    }
+}
+
+/// Regions of a grid, along with the region each position belongs to.
+#[derive(Debug, Clone)]
+pub struct Regions {
+    /// Index into `regions` for each `(x, y)` position, as `labels[y][x]`, or `None` for positions
+    /// left out of every region.
+    pub labels: Vec<Vec<Option<usize>>>,
+    pub regions: Vec<Region>,
+}
+
+impl Regions {
+    pub fn region_at(&self, x: usize, y: usize) -> Option<&Region> {
+        self.labels[y][x].map(|label| &self.regions[label])
+    }
+}
+
+/// Regions of equal, connected cells, see [`regions_by`].
+pub fn regions<T: PartialEq>(grid: &[Vec<T>], include_diagonals: bool) -> Regions {
+    regions_by(grid, include_diagonals, T::eq)
+}
+
+/// Regions of connected cells, where neighbors are connected if `same` holds for them. Diagonal
+/// neighbors only connect with `include_diagonals`, though perimeters and sides are always measured
+/// along the edges of cells.
+pub fn regions_by<T>(
+    grid: &[Vec<T>],
+    include_diagonals: bool,
+    same: impl Fn(&T, &T) -> bool,
+) -> Regions {
+    label(grid, include_diagonals, |_| true, same)
+}
+
+/// Regions of connected cells for which `predicate` holds, leaving out all other cells.
+pub fn regions_where<T>(
+    grid: &[Vec<T>],
+    include_diagonals: bool,
+    predicate: impl Fn(&T) -> bool,
+) -> Regions {
+    label(grid, include_diagonals, &predicate, |_, b| predicate(b))
+}
+
+fn label<T>(
+    grid: &[Vec<T>],
+    include_diagonals: bool,
+    include: impl Fn(&T) -> bool,
+    same: impl Fn(&T, &T) -> bool,
+) -> Regions {
+    let mut labels: Vec<Vec<Option<usize>>> = grid.iter().map(|r| vec![None; r.len()]).collect();
+    let mut positions = Vec::new();
+
+    for y in 0..grid.len() {
+        for x in 0..grid[y].len() {
+            if labels[y][x].is_some() || !include(&grid[y][x]) {
+                continue;
+            }
+
+            let search = bfs((x, y), |&(x, y)| {
+                crate::neighbors(grid, x, y, include_diagonals)
+                    .into_iter()
+                    .map(<(usize, usize)>::from)
+                    .filter(|&(n_x, n_y)| same(&grid[y][x], &grid[n_y][n_x]))
+                    .collect::<Vec<_>>()
+            });
+
+            for &(x, y) in &search.order {
+                labels[y][x] = Some(positions.len());
+            }
+            positions.push(search.order);
+        }
+    }
+
+    let regions = positions
+        .into_iter()
+        .map(|positions| measure(&labels, positions))
+        .collect();
+
+    Regions { labels, regions }
+}
+
+fn measure(labels: &[Vec<Option<usize>>], positions: Vec<(usize, usize)>) -> Region {
+    let (x, y) = positions[0];
+    let label = labels[y][x];
+
+    // Whether the cell at (x, y) has an edge on its `direction` side
+    let has_edge = |x: usize, y: usize, direction: Direction| {
+        labels[y][x] == label
+            && crate::neighbor_in_direction(labels, direction, x, y)
+                .is_none_or(|n| labels[n.position.1][n.position.0] != label)
+    };
+
+    let mut perimeter = 0;
+    let mut sides = 0;
+    for &(x, y) in &positions {
+        for direction in Direction::ORTHOGONAL {
+            if !has_edge(x, y, direction) {
+                continue;
+            }
+            perimeter += 1;
+
+            // Only the topmost or leftmost edge of a side counts it
+            let previous = match direction {
+                Direction::Up | Direction::Down => Direction::Left,
+                _ => Direction::Up,
+            };
+            let continues = crate::neighbor_in_direction(labels, previous, x, y)
+                .is_some_and(|n| has_edge(n.position.0, n.position.1, direction));
+            if !continues {
+                sides += 1;
+            }
+        }
+    }
+
+    let bounds: BoundingBox<usize> = positions.iter().copied().collect();
+    let bounds = bounds
+        .min()
+        .zip(bounds.max())
+        .expect("regions have at least one position");
+
+    Region {
+        positions,
+        perimeter,
+        sides,
+        bounds,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn grid(rows: &[&str]) -> Vec<Vec<char>> {
+        rows.iter().map(|r| r.chars().collect()).collect()
+    }
+
+    fn prices(grid: &[Vec<char>]) -> (usize, usize) {
+        let regions = regions(grid, false).regions;
+        (
+            regions.iter().map(|r| r.area() * r.perimeter).sum(),
+            regions.iter().map(|r| r.area() * r.sides).sum(),
+        )
+    }
+
+    #[test]
+    fn test_regions() {
+        let garden = grid(&["AAAA", "BBCD", "BBCC", "EEEC"]);
+        let regions = regions(&garden, false);
+
+        let measured = regions
+            .regions
+            .iter()
+            .map(|r| (r.area(), r.perimeter, r.sides))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![(4, 10, 4), (4, 8, 4), (4, 10, 8), (1, 4, 4), (3, 8, 4)],
+            measured
+        );
+
+        let c = regions.region_at(2, 1).unwrap();
+        assert_eq!(((2, 1), (3, 3)), c.bounds);
+        assert_eq!(regions.labels[3][3], regions.labels[1][2]);
+        assert_ne!(regions.labels[1][3], regions.labels[1][2]);
+    }
+
+    #[test]
+    fn test_prices() {
+        assert_eq!((140, 80), prices(&grid(&["AAAA", "BBCD", "BBCC", "EEEC"])));
+        assert_eq!(
+            (772, 436),
+            prices(&grid(&["OOOOO", "OXOXO", "OOOOO", "OXOXO", "OOOOO"]))
+        );
+        assert_eq!(
+            236,
+            prices(&grid(&["EEEEE", "EXXXX", "EEEEE", "EXXXX", "EEEEE"])).1
+        );
+        assert_eq!(
+            368,
+            prices(&grid(&[
+                "AAAAAA", "AAABBA", "AAABBA", "ABBAAA", "ABBAAA", "AAAAAA"
+            ]))
+            .1
+        );
+        assert_eq!(
+            (1930, 1206),
+            prices(&grid(&[
+                "RRRRIICCFF",
+                "RRRRIICCCF",
+                "VVRRRCCFFF",
+                "VVRCCCJFFF",
+                "VVVVCJJCFE",
+                "VVIVCCJJEE",
+                "VVIIICJJEE",
+                "MIIIIIJJEE",
+                "MIIISIJEEE",
+                "MMMISSJEEE",
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_diagonals_and_predicates() {
+        let garden = grid(&["#..", ".#.", "..#"]);
+
+        assert_eq!(5, regions(&garden, false).regions.len());
+        let diagonal = regions(&garden, true);
+        assert_eq!(2, diagonal.regions.len());
+        let line = diagonal.region_at(1, 1).unwrap();
+        assert_eq!(3, line.area());
+        assert_eq!(12, line.perimeter);
+        assert_eq!(12, line.sides);
+        assert_eq!(((0, 0), (2, 2)), line.bounds);
+
+        let walls = regions_where(&garden, false, |&c| c == '#');
+        assert_eq!(3, walls.regions.len());
+        assert_eq!(None, walls.labels[0][1]);
+        assert_eq!(None, walls.region_at(2, 0));
+
+        // Similar but not equal cells connect
+        let letters = regions_by(&grid(&["aA", "bB"]), false, |a, b| {
+            a.eq_ignore_ascii_case(b)
+        });
+        assert_eq!(2, letters.regions.len());
+        assert_eq!(4, letters.regions[0].sides);
+    }
+}
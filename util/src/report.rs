@@ -0,0 +1,152 @@
+use std::fmt::Display;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use log::debug;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::{Format, Part, Parts};
+
+/// Times parsing and solving, and prints each answer in the selected [`Format`]. Text prints
+/// `label: answer` lines, JSON prints an array of records once the day has finished and NDJSON
+/// prints each record as soon as its answer is known.
+#[derive(Debug)]
+pub struct Reporter {
+    day: u32,
+    parts: Parts,
+    format: Format,
+    input_hash: Option<String>,
+    parse_duration: Duration,
+    records: Vec<Value>,
+}
+
+impl Reporter {
+    pub fn new(day: u32, parts: Parts, format: Format, input_hash: Option<String>) -> Self {
+        Self {
+            day,
+            parts,
+            format,
+            input_hash,
+            parse_duration: Duration::ZERO,
+            records: Vec::new(),
+        }
+    }
+
+    /// Runs `f`, counting the time it takes towards the parse duration of every answer.
+    pub fn parse<T>(&mut self, f: impl FnOnce() -> Result<T>) -> Result<T> {
+        let start = Instant::now();
+        let parsed = f();
+        self.parse_duration += start.elapsed();
+        debug!("Parsed in {:?}", self.parse_duration);
+        parsed
+    }
+
+    /// Runs `f` and reports its answer, if `part` was selected on the command line.
+    pub fn solve<T: Display>(
+        &mut self,
+        part: Part,
+        label: &str,
+        f: impl FnOnce() -> Result<T>,
+    ) -> Result<()> {
+        if !self.parts.contains(part) {
+            return Ok(());
+        }
+
+        let start = Instant::now();
+        let answer = f()?;
+        let solve_duration = start.elapsed();
+        debug!("Solved part {part} in {solve_duration:?}");
+
+        let record = self.record(part, label, &answer, solve_duration);
+        match self.format {
+            Format::Text => println!("{label}: {answer}"),
+            Format::Ndjson => println!("{record}"),
+            Format::Json => (),
+        }
+        self.records.push(record);
+
+        Ok(())
+    }
+
+    /// Prints output other than answers, e.g. traces, to stderr for the machine-readable formats so
+    /// that stdout only holds records.
+    pub fn diagnostic(&self, text: impl Display) {
+        match self.format {
+            Format::Text => println!("{text}"),
+            Format::Json | Format::Ndjson => eprintln!("{text}"),
+        }
+    }
+
+    /// Prints anything held back until every answer is known.
+    pub fn finish(self) {
+        if self.format == Format::Json {
+            println!("{:#}", Value::Array(self.records));
+        }
+    }
+
+    fn record(&self, part: Part, label: &str, answer: &impl Display, solve: Duration) -> Value {
+        json!({
+            "day": self.day,
+            "part": part.to_string(),
+            "label": label,
+            "answer": answer.to_string(),
+            "parse_micros": self.parse_duration.as_micros() as u64,
+            "solve_micros": solve.as_micros() as u64,
+            "input_sha256": self.input_hash,
+        })
+    }
+}
+
+/// Hex-encoded SHA-256 of the file at `path`, read a block at a time.
+pub(crate) fn hash_file(path: impl AsRef<Path>) -> Result<String> {
+    let path = path.as_ref();
+    let mut file =
+        File::open(path).with_context(|| format!("Failed to hash {}", path.display()))?;
+
+    let mut hasher = Sha256::new();
+    let mut buffer = [0; 64 * 1024];
+    loop {
+        match file.read(&mut buffer)? {
+            0 => break,
+            n => hasher.update(&buffer[..n]),
+        }
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_record() -> Result<()> {
+        let mut reporter = Reporter::new(2, Parts::Two, Format::Json, Some("abc".to_string()));
+
+        reporter.parse(|| Ok(()))?;
+        reporter.solve(Part::One, "Skipped", || -> Result<usize> {
+            panic!("part 1 wasn't selected")
+        })?;
+        reporter.solve(Part::Two, "Safety Count", || Ok(4))?;
+
+        assert_eq!(1, reporter.records.len());
+        let record = &reporter.records[0];
+        assert_eq!(json!(2), record["day"]);
+        assert_eq!(json!("2"), record["part"]);
+        assert_eq!(json!("Safety Count"), record["label"]);
+        assert_eq!(json!("4"), record["answer"]);
+        assert_eq!(json!("abc"), record["input_sha256"]);
+        assert!(record["parse_micros"].is_u64());
+        assert!(record["solve_micros"].is_u64());
+
+        Ok(())
+    }
+}
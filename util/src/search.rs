@@ -0,0 +1,379 @@
+//! Graph searches over nodes given by a neighbor closure, so they work on grids, implicit state
+//! spaces or anything else that can list where it's possible to go next.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// Everything reached by a search from its start.
+#[derive(Debug, Clone)]
+pub struct Search<N, C> {
+    /// Cost of the path found to each reached node, steps for unweighted searches.
+    pub distances: HashMap<N, C>,
+    /// The node each reached node was reached from, absent for the start.
+    pub predecessors: HashMap<N, N>,
+    /// Reached nodes in the order the search settled them.
+    pub order: Vec<N>,
+}
+
+impl<N: Clone + Hash + Eq, C: Copy> Search<N, C> {
+    fn new() -> Self {
+        Self {
+            distances: HashMap::new(),
+            predecessors: HashMap::new(),
+            order: Vec::new(),
+        }
+    }
+
+    pub fn distance(&self, node: &N) -> Option<C> {
+        self.distances.get(node).copied()
+    }
+
+    /// Nodes from the start to `target`, inclusive, or `None` if `target` wasn't reached.
+    pub fn path(&self, target: &N) -> Option<Vec<N>> {
+        if !self.distances.contains_key(target) {
+            return None;
+        }
+
+        let mut path = vec![target.clone()];
+        while let Some(previous) = self.predecessors.get(path.last()?) {
+            path.push(previous.clone());
+        }
+        path.reverse();
+
+        Some(path)
+    }
+}
+
+/// Breadth-first search, finding the fewest steps to every node reachable from `start`.
+pub fn bfs<N, I>(start: N, mut neighbors: impl FnMut(&N) -> I) -> Search<N, usize>
+where
+    N: Clone + Hash + Eq,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new();
+    search.distances.insert(start.clone(), 0);
+
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        let distance = search.distances[&node];
+
+        for next in neighbors(&node) {
+            if !search.distances.contains_key(&next) {
+                search.distances.insert(next.clone(), distance + 1);
+                search.predecessors.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+
+        search.order.push(node);
+    }
+
+    search
+}
+
+/// Depth-first search, visiting neighbors in the order they're given. Distances are the steps
+/// along the path the search took, which need not be the shortest.
+pub fn dfs<N, I>(start: N, mut neighbors: impl FnMut(&N) -> I) -> Search<N, usize>
+where
+    N: Clone + Hash + Eq,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new();
+
+    let mut stack = vec![(start, None, 0)];
+    while let Some((node, previous, distance)) = stack.pop() {
+        if search.distances.contains_key(&node) {
+            continue;
+        }
+
+        search.distances.insert(node.clone(), distance);
+        if let Some(previous) = previous {
+            search.predecessors.insert(node.clone(), previous);
+        }
+
+        let next: Vec<N> = neighbors(&node)
+            .into_iter()
+            .filter(|n| !search.distances.contains_key(n))
+            .collect();
+        stack.extend(
+            next.into_iter()
+                .rev()
+                .map(|n| (n, Some(node.clone()), distance + 1)),
+        );
+
+        search.order.push(node);
+    }
+
+    search
+}
+
+/// Dijkstra's algorithm, finding the cheapest path to every node reachable from `start`, where
+/// `cost` gives the non-negative cost of a step between two neighbors.
+pub fn dijkstra<N, C, I>(
+    start: N,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut cost: impl FnMut(&N, &N) -> C,
+) -> Search<N, C>
+where
+    N: Clone + Hash + Eq,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new();
+    search.distances.insert(start.clone(), C::default());
+
+    let mut settled = HashSet::new();
+    let mut queue = BinaryHeap::from([Queued {
+        priority: C::default(),
+        node: start,
+    }]);
+    while let Some(Queued { node, .. }) = queue.pop() {
+        if !settled.insert(node.clone()) {
+            continue;
+        }
+
+        let distance = search.distances[&node];
+        for next in neighbors(&node) {
+            let candidate = distance + cost(&node, &next);
+            if search.distances.get(&next).is_none_or(|&d| candidate < d) {
+                search.distances.insert(next.clone(), candidate);
+                search.predecessors.insert(next.clone(), node.clone());
+                queue.push(Queued {
+                    priority: candidate,
+                    node: next,
+                });
+            }
+        }
+
+        search.order.push(node);
+    }
+
+    search
+}
+
+/// A* search for the cheapest path from `start` to a node satisfying `is_goal`, returning its cost
+/// and nodes. `heuristic` must never overestimate the remaining cost for the path to be cheapest.
+pub fn a_star<N, C, I>(
+    start: N,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut cost: impl FnMut(&N, &N) -> C,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<(C, Vec<N>)>
+where
+    N: Clone + Hash + Eq,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new();
+    search.distances.insert(start.clone(), C::default());
+
+    let mut settled = HashSet::new();
+    let mut queue = BinaryHeap::from([Queued {
+        priority: heuristic(&start),
+        node: start,
+    }]);
+    while let Some(Queued { node, .. }) = queue.pop() {
+        if is_goal(&node) {
+            return Some((search.distances[&node], search.path(&node)?));
+        }
+        if !settled.insert(node.clone()) {
+            continue;
+        }
+
+        let distance = search.distances[&node];
+        for next in neighbors(&node) {
+            let candidate = distance + cost(&node, &next);
+            if search.distances.get(&next).is_none_or(|&d| candidate < d) {
+                search.distances.insert(next.clone(), candidate);
+                search.predecessors.insert(next.clone(), node.clone());
+                queue.push(Queued {
+                    priority: candidate + heuristic(&next),
+                    node: next,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+/// A node in a priority queue, popped cheapest first and compared by priority alone so nodes
+/// needn't be ordered.
+struct Queued<N, C> {
+    priority: C,
+    node: N,
+}
+
+impl<N, C: Ord> Ord for Queued<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+impl<N, C: Ord> PartialOrd for Queued<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> PartialEq for Queued<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N, C: Ord> Eq for Queued<N, C> {}
+
+/// Neighbor closure for searching a dense grid by `(x, y)` position, see [`crate::neighbors`].
+/// `passable` is given the cells being moved from and to.
+pub fn grid_neighbors<'a, T>(
+    grid: &'a [Vec<T>],
+    include_diagonals: bool,
+    passable: impl Fn(&T, &T) -> bool + 'a,
+) -> impl FnMut(&(usize, usize)) -> Vec<(usize, usize)> + 'a {
+    move |&(x, y)| {
+        crate::neighbors(grid, x, y, include_diagonals)
+            .into_iter()
+            .map(<(usize, usize)>::from)
+            .filter(|&(n_x, n_y)| passable(&grid[y][x], &grid[n_y][n_x]))
+            .collect()
+    }
+}
+
+/// Manhattan distance between two grid positions, a heuristic for [`a_star`] on grids without
+/// diagonal moves.
+pub fn manhattan_distance(a: (usize, usize), b: (usize, usize)) -> usize {
+    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn maze() -> Vec<Vec<char>> {
+        ["S.#.....", ".##.###.", "....#...", "#.#.#.#.", "..#...#E"]
+            .iter()
+            .map(|l| l.chars().collect())
+            .collect()
+    }
+
+    fn open(_: &char, to: &char) -> bool {
+        *to != '#'
+    }
+
+    #[test]
+    fn test_bfs() {
+        let maze = maze();
+        let search = bfs((0, 0), grid_neighbors(&maze, false, open));
+
+        assert_eq!(Some(15), search.distance(&(7, 4)));
+        let path = search.path(&(7, 4)).unwrap();
+        assert_eq!(16, path.len());
+        assert_eq!(Some(&(0, 0)), path.first());
+        assert!(path
+            .windows(2)
+            .all(|p| manhattan_distance(p[0], p[1]) == 1 && maze[p[1].1][p[1].0] != '#'));
+
+        // Walls are never reached
+        assert_eq!(None, search.distance(&(2, 0)));
+        assert_eq!(None, search.path(&(2, 0)));
+        assert_eq!(Some(&(0, 0)), search.order.first());
+    }
+
+    #[test]
+    fn test_dfs() {
+        let maze = maze();
+        let breadth = bfs((0, 0), grid_neighbors(&maze, false, open));
+        let depth = dfs((0, 0), grid_neighbors(&maze, false, open));
+
+        // Both reach everything reachable, though not necessarily by the shortest path
+        assert_eq!(
+            breadth.distances.keys().collect::<HashSet<_>>(),
+            depth.distances.keys().collect::<HashSet<_>>()
+        );
+        assert_eq!(breadth.order.len(), depth.order.len());
+        for node in depth.distances.keys() {
+            assert!(depth.distance(node) >= breadth.distance(node));
+            assert_eq!(
+                depth.distance(node).unwrap() + 1,
+                depth.path(node).unwrap().len()
+            );
+        }
+
+        // A line is visited in order
+        let line = dfs(0, |&n: &u32| if n < 5 { vec![n + 1] } else { vec![] });
+        assert_eq!(vec![0, 1, 2, 3, 4, 5], line.order);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        // The direct edge is more expensive than going the long way round
+        let edges: HashMap<char, Vec<(char, u64)>> = HashMap::from([
+            ('a', vec![('b', 7), ('c', 1)]),
+            ('b', vec![('d', 1)]),
+            ('c', vec![('e', 2)]),
+            ('e', vec![('b', 1), ('d', 10)]),
+            ('f', vec![('a', 1)]),
+        ]);
+        let neighbors = |n: &char| {
+            edges
+                .get(n)
+                .into_iter()
+                .flatten()
+                .map(|&(n, _)| n)
+                .collect::<Vec<_>>()
+        };
+        let cost = |a: &char, b: &char| edges[a].iter().find(|(n, _)| n == b).unwrap().1;
+
+        let search = dijkstra('a', neighbors, cost);
+        assert_eq!(Some(4), search.distance(&'b'));
+        assert_eq!(Some(5), search.distance(&'d'));
+        assert_eq!(Some(vec!['a', 'c', 'e', 'b', 'd']), search.path(&'d'));
+        assert_eq!(None, search.distance(&'f'));
+        assert_eq!(vec!['a', 'c', 'e', 'b', 'd'], search.order);
+    }
+
+    #[test]
+    fn test_a_star() {
+        let maze = maze();
+        let goal = (7, 4);
+
+        let (cost, path) = a_star(
+            (0, 0),
+            grid_neighbors(&maze, false, open),
+            |_, _| 1,
+            |&p| manhattan_distance(p, goal),
+            |&p| p == goal,
+        )
+        .unwrap();
+        assert_eq!(15, cost);
+        assert_eq!(
+            bfs((0, 0), grid_neighbors(&maze, false, open)).distance(&goal),
+            Some(path.len() - 1)
+        );
+
+        // Moving diagonally through gaps in the walls
+        let diagonal = a_star(
+            (0, 0),
+            grid_neighbors(&maze, true, open),
+            |_, _| 1,
+            |_| 0,
+            |&p| p == goal,
+        );
+        assert_eq!(Some(9), diagonal.map(|(c, _)| c));
+
+        assert_eq!(
+            None,
+            a_star(
+                (0, 0),
+                grid_neighbors(&maze, false, open),
+                |_, _| 1,
+                |_| 0,
+                |&p| p == (2, 0)
+            )
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use std::fmt::{Debug, Display};
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::{Input, Part, ReadOptions, TEST_INPUT_PATH};
+
+/// A day's parsed input, which can answer either part of the puzzle with default settings.
+pub trait Solution: Sized {
+    type Answer: Display + Debug + PartialEq;
+
+    fn parse(input: Input) -> Result<Self>;
+
+    fn solve(&self, part: Part) -> Result<Self::Answer>;
+}
+
+/// Initializes test logging and reads an example input. A named example is read from
+/// `input/example-{name}`. Otherwise `input/example-part{N}` is used if the part has its own
+/// example, falling back to the day's `input/example`.
+pub fn example_input(part: Part, name: Option<&str>) -> Result<Input> {
+    crate::init_test_logger()?;
+
+    let path = match name {
+        Some(name) => format!("{TEST_INPUT_PATH}-{name}"),
+        None => {
+            let path = format!("{TEST_INPUT_PATH}-part{part}");
+            if Path::new(&path).exists() {
+                path
+            } else {
+                TEST_INPUT_PATH.to_string()
+            }
+        }
+    };
+
+    crate::read_input(path, ReadOptions::default())
+}
+
+/// Generates a test that parses an example with [`Solution::parse`] and checks the answer to a
+/// part, e.g. `example_test!(part_1_example, Locations, Part::One, 11);`. A named example can be
+/// given before the expected answer, see [`example_input`].
+#[macro_export]
+macro_rules! example_test {
+    ($(#[$attr:meta])* $test:ident, $solution:ty, $part:expr, $expected:expr) => {
+        $crate::example_test!(@test $(#[$attr])* $test, $solution, $part, None, $expected);
+    };
+    ($(#[$attr:meta])* $test:ident, $solution:ty, $part:expr, $name:literal, $expected:expr) => {
+        $crate::example_test!(@test $(#[$attr])* $test, $solution, $part, Some($name), $expected);
+    };
+    (@test $(#[$attr:meta])* $test:ident, $solution:ty, $part:expr, $name:expr, $expected:expr) => {
+        #[test]
+        $(#[$attr])*
+        fn $test() -> ::anyhow::Result<()> {
+            let input = $crate::example_input($part, $name)?;
+            let solution = <$solution as $crate::Solution>::parse(input)?;
+            assert_eq!($expected, $crate::Solution::solve(&solution, $part)?);
+            Ok(())
+        }
+    };
+}
+
+/// Generates a test that parses inputs from [`generate`](crate::generate) functions with
+/// [`Solution::parse`] and checks the answer to a part that each was generated with, e.g.
+/// `generated_test!(generated_input, Calibrations, Part::One, |rng| calibrations(rng, 500, 0.5));`.
+#[macro_export]
+macro_rules! generated_test {
+    ($test:ident, $solution:ty, $part:expr, $($generate:expr),+ $(,)?) => {
+        #[test]
+        fn $test() -> ::anyhow::Result<()> {
+            let generators: &[fn(
+                &mut $crate::generate::Rng,
+            ) -> (String, <$solution as $crate::Solution>::Answer)] = &[$($generate),+];
+
+            let mut rng = $crate::generate::Rng::new(1);
+            for generate in generators {
+                let (input, expected) = generate(&mut rng);
+                let solution = <$solution as $crate::Solution>::parse($crate::Input::from(input))?;
+                assert_eq!(expected, $crate::Solution::solve(&solution, $part)?);
+            }
+            Ok(())
+        }
+    };
+}
+
+/// Generates a property test that parses inputs from a proptest strategy with
+/// [`Solution::parse`] and solves both parts, checking that nothing panics, e.g.
+/// `arbitrary_input_test!(arbitrary_input_does_not_panic, Locations, "\\PC{0,64}");`.
+#[macro_export]
+macro_rules! arbitrary_input_test {
+    ($test:ident, $solution:ty, $strategy:expr) => {
+        ::proptest::proptest! {
+            #[test]
+            fn $test(input in $strategy) {
+                let input = $crate::Input::from(input);
+                if let Ok(solution) = <$solution as $crate::Solution>::parse(input) {
+                    let _ = $crate::Solution::solve(&solution, $crate::Part::One);
+                    let _ = $crate::Solution::solve(&solution, $crate::Part::Two);
+                }
+            }
+        }
+    };
+}
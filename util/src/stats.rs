@@ -0,0 +1,416 @@
+use std::collections::BTreeMap;
+use std::ops::{RangeInclusive, Sub};
+
+use crate::math::Integer;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MinMax<T> {
+    pub min: Option<T>,
+    pub max: Option<T>,
+}
+
+impl<T: Ord + Clone> MinMax<T> {
+    pub fn new() -> Self {
+        Self {
+            min: None,
+            max: None,
+        }
+    }
+
+    pub fn push(&mut self, value: T) {
+        if self.min.as_ref().is_none_or(|min| value < *min) {
+            self.min = Some(value.clone());
+        }
+        if self.max.as_ref().is_none_or(|max| value > *max) {
+            self.max = Some(value);
+        }
+    }
+
+    pub fn merge(&mut self, other: Self) {
+        self.extend(other.min);
+        self.extend(other.max);
+    }
+
+    pub fn range(&self) -> Option<RangeInclusive<T>> {
+        Some(self.min.clone()?..=self.max.clone()?)
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.range().is_some_and(|r| r.contains(value))
+    }
+}
+
+impl<T: Ord + Clone + Sub<Output = T>> MinMax<T> {
+    pub fn span(&self) -> Option<T> {
+        Some(self.max.clone()? - self.min.clone()?)
+    }
+}
+
+impl<T: Ord + Clone> Default for MinMax<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Clone> Extend<T> for MinMax<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T: Ord + Clone> FromIterator<T> for MinMax<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut min_max = Self::new();
+        min_max.extend(iter);
+        min_max
+    }
+}
+
+/// Smallest box containing every `(x, y)` point seen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundingBox<T> {
+    pub x: MinMax<T>,
+    pub y: MinMax<T>,
+}
+
+impl<T: Ord + Clone> BoundingBox<T> {
+    pub fn new() -> Self {
+        Self {
+            x: MinMax::new(),
+            y: MinMax::new(),
+        }
+    }
+
+    pub fn push(&mut self, (x, y): (T, T)) {
+        self.x.push(x);
+        self.y.push(y);
+    }
+
+    pub fn merge(&mut self, other: Self) {
+        self.x.merge(other.x);
+        self.y.merge(other.y);
+    }
+
+    pub fn min(&self) -> Option<(T, T)> {
+        Some((self.x.min.clone()?, self.y.min.clone()?))
+    }
+
+    pub fn max(&self) -> Option<(T, T)> {
+        Some((self.x.max.clone()?, self.y.max.clone()?))
+    }
+
+    pub fn contains(&self, (x, y): &(T, T)) -> bool {
+        self.x.contains(x) && self.y.contains(y)
+    }
+}
+
+impl<T: Ord + Clone> Default for BoundingBox<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Clone> Extend<(T, T)> for BoundingBox<T> {
+    fn extend<I: IntoIterator<Item = (T, T)>>(&mut self, iter: I) {
+        for point in iter {
+            self.push(point);
+        }
+    }
+}
+
+impl<T: Ord + Clone> FromIterator<(T, T)> for BoundingBox<T> {
+    fn from_iter<I: IntoIterator<Item = (T, T)>>(iter: I) -> Self {
+        let mut bounds = Self::new();
+        bounds.extend(iter);
+        bounds
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mean<T> {
+    count: usize,
+    sum: Option<T>,
+}
+
+impl<T: Integer> Mean<T> {
+    pub fn new() -> Self {
+        Self {
+            count: 0,
+            sum: Some(T::ZERO),
+        }
+    }
+
+    pub fn push(&mut self, value: T) {
+        self.count += 1;
+        self.sum = self.sum.and_then(|sum| sum.checked_add(value));
+    }
+
+    pub fn merge(&mut self, other: Self) {
+        self.count += other.count;
+        self.sum = self.sum.zip(other.sum).and_then(|(a, b)| a.checked_add(b));
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// `None` if the sum has overflowed.
+    pub fn sum(&self) -> Option<T> {
+        self.sum
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        match self.count {
+            0 => None,
+            count => Some(self.sum?.as_f64() / count as f64),
+        }
+    }
+}
+
+impl<T: Integer> Default for Mean<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Integer> Extend<T> for Mean<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T: Integer> FromIterator<T> for Mean<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut mean = Self::new();
+        mean.extend(iter);
+        mean
+    }
+}
+
+/// Keeps every value seen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Median<T> {
+    values: Vec<T>,
+}
+
+impl<T: Ord + Clone> Median<T> {
+    pub fn new() -> Self {
+        Self { values: Vec::new() }
+    }
+
+    pub fn push(&mut self, value: T) {
+        self.values.push(value);
+    }
+
+    pub fn merge(&mut self, other: Self) {
+        self.values.extend(other.values);
+    }
+
+    /// Lower and upper middle values, which are the same for an odd count.
+    pub fn medians(&self) -> Option<(T, T)> {
+        if self.values.is_empty() {
+            return None;
+        }
+
+        let mut values = self.values.clone();
+        let upper = values.len() / 2;
+
+        let (lower_half, upper_median, _) = values.select_nth_unstable(upper);
+        let upper_median = upper_median.clone();
+        let lower_median = match self.values.len() % 2 {
+            1 => upper_median.clone(),
+            _ => lower_half.iter().max()?.clone(),
+        };
+
+        Some((lower_median, upper_median))
+    }
+
+    /// Lower middle value.
+    pub fn median(&self) -> Option<T> {
+        self.medians().map(|(lower, _)| lower)
+    }
+}
+
+impl<T: Ord + Clone> Default for Median<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Clone> Extend<T> for Median<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.values.extend(iter);
+    }
+}
+
+impl<T: Ord + Clone> FromIterator<T> for Median<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self {
+            values: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mode<T> {
+    counts: BTreeMap<T, usize>,
+}
+
+impl<T: Ord + Clone> Mode<T> {
+    pub fn new() -> Self {
+        Self {
+            counts: BTreeMap::new(),
+        }
+    }
+
+    pub fn push(&mut self, value: T) {
+        *self.counts.entry(value).or_default() += 1;
+    }
+
+    pub fn merge(&mut self, other: Self) {
+        for (value, count) in other.counts {
+            *self.counts.entry(value).or_default() += count;
+        }
+    }
+
+    pub fn count(&self, value: &T) -> usize {
+        self.counts.get(value).copied().unwrap_or_default()
+    }
+
+    /// Most common value and its count, preferring the least value on a tie.
+    pub fn mode(&self) -> Option<(T, usize)> {
+        self.counts
+            .iter()
+            .rev()
+            .max_by_key(|(_, &count)| count)
+            .map(|(value, &count)| (value.clone(), count))
+    }
+}
+
+impl<T: Ord + Clone> Default for Mode<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Clone> Extend<T> for Mode<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T: Ord + Clone> FromIterator<T> for Mode<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut mode = Self::new();
+        mode.extend(iter);
+        mode
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_min_max() {
+        let mut min_max: MinMax<usize> = [5, 3, 9, 4].into_iter().collect();
+        assert_eq!(Some(3), min_max.min);
+        assert_eq!(Some(9), min_max.max);
+        assert_eq!(Some(3..=9), min_max.range());
+        assert_eq!(Some(6), min_max.span());
+        assert!(min_max.contains(&9));
+        assert!(!min_max.contains(&10));
+
+        min_max.push(1);
+        min_max.merge([20, 7].into_iter().collect());
+        assert_eq!(Some(1..=20), min_max.range());
+        min_max.merge(MinMax::new());
+        assert_eq!(Some(19), min_max.span());
+
+        let empty = MinMax::<i32>::new();
+        assert_eq!(None, empty.range());
+        assert_eq!(None, empty.span());
+        assert!(!empty.contains(&0));
+
+        let words: MinMax<&str> = ["pear", "apple", "quince"].into_iter().collect();
+        assert_eq!(Some("apple"..="quince"), words.range());
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let mut bounds: BoundingBox<i64> = [(3, -1), (-2, 4), (0, 0)].into_iter().collect();
+        assert_eq!(Some((-2, -1)), bounds.min());
+        assert_eq!(Some((3, 4)), bounds.max());
+        assert_eq!(Some(5), bounds.x.span());
+        assert!(bounds.contains(&(3, -1)));
+        // Within the box, though not a point that was seen
+        assert!(bounds.contains(&(-2, -1)));
+        assert!(!bounds.contains(&(4, 0)));
+
+        bounds.merge([(10, 10)].into_iter().collect());
+        assert_eq!(Some((10, 10)), bounds.max());
+        assert_eq!(None, BoundingBox::<u8>::new().min());
+    }
+
+    #[test]
+    fn test_mean() {
+        let mut mean: Mean<u32> = [1, 2, 3, 4].into_iter().collect();
+        assert_eq!(4, mean.count());
+        assert_eq!(Some(10), mean.sum());
+        assert_eq!(Some(2.5), mean.mean());
+
+        mean.merge([5].into_iter().collect());
+        assert_eq!(Some(3.0), mean.mean());
+
+        assert_eq!(None, Mean::<i8>::new().mean());
+        assert_eq!(
+            Some(-2.0),
+            [-1i8, -3].into_iter().collect::<Mean<_>>().mean()
+        );
+
+        let overflowed: Mean<u8> = [200, 100].into_iter().collect();
+        assert_eq!(None, overflowed.sum());
+        assert_eq!(None, overflowed.mean());
+        let mut merged: Mean<u8> = [200].into_iter().collect();
+        merged.merge([100].into_iter().collect());
+        assert_eq!(None, merged.sum());
+    }
+
+    #[test]
+    fn test_median() {
+        let mut median: Median<i32> = [7, 1, 3].into_iter().collect();
+        assert_eq!(Some((3, 3)), median.medians());
+
+        median.push(10);
+        assert_eq!(Some((3, 7)), median.medians());
+        assert_eq!(Some(3), median.median());
+
+        median.merge([2, 2].into_iter().collect());
+        assert_eq!(Some((2, 3)), median.medians());
+
+        assert_eq!(None, Median::<i32>::new().median());
+        assert_eq!(
+            Some((5, 5)),
+            [5, 5].into_iter().collect::<Median<_>>().medians()
+        );
+    }
+
+    #[test]
+    fn test_mode() {
+        let mut mode: Mode<char> = "mississippi".chars().collect();
+        // 'i' and 's' both appear 4 times
+        assert_eq!(Some(('i', 4)), mode.mode());
+        assert_eq!(2, mode.count(&'p'));
+        assert_eq!(0, mode.count(&'z'));
+
+        mode.merge("ss".chars().collect());
+        assert_eq!(Some(('s', 6)), mode.mode());
+        assert_eq!(None, Mode::<u8>::new().mode());
+    }
+}